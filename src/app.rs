@@ -1,31 +1,59 @@
-use minefield_rs::{Minefield, SpotState, StepResult, FlagToggleResult};
+use crate::minefield::{Heading, Spot, SpotLook, SpotState, Topology};
+use crate::solver::Deduction;
+use crate::probability;
+use crate::replay::{Replay, ReplayViewer};
+use crate::game::{Game, GameEvent, GameState, SavedGame};
+pub use crate::game::{GameConfig, GameDifficulty, FirstClick, Generator, PlayMode, Seed};
+use crate::board::Board;
+use crate::records::{Records, FinishedGame};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::settings_file::SettingsFile;
+use std::collections::HashMap;
 
 use eframe::{
-    egui::{PointerButton, self, Layout, Label, RichText, Button, Context, TextStyle, Ui, CentralPanel, Sense, Direction, TopBottomPanel, Window, ComboBox},
-    epaint::{Color32, Vec2},
-    emath::{Align},
+    egui::{plot::{Plot, BarChart, Bar}, PointerButton, self, Layout, Label, RichText, Button, Context, TextStyle, Ui, CentralPanel, Sense, Direction, TopBottomPanel, Window, ComboBox, Grid, TextEdit, Slider, DragValue, SelectableLabel, Key, Modifiers, CollapsingHeader, Response, Visuals},
+    epaint::{Color32, Vec2, Stroke, Shape, FontId},
+    emath::{Align, Align2},
     Frame, App, CreationContext,
 };
 use egui_extras::{TableBuilder, Size};
 use serde::{Serialize, Deserialize};
-use std::sync::mpsc::{channel, Receiver};
-
-// Native timer
-#[cfg(not(target_arch = "wasm32"))]
-use timer::{Timer, Guard};
-
-// WASM timer
-#[cfg(target_arch = "wasm32")]
-use gloo_timers::callback::Interval;
 
 pub struct MinesweepRsApp {
-    minefield: Minefield,
-    placed_flags: u32,
-    timer: AppTimer,
-    seconds_lapsed: i32,
-    game_state: GameState,
+    game: Game,
     game_config: GameConfig,
     ui_toolbar_group: UiToolbarGroup,
+    /// High scores, statistics and replays of the finished games
+    records: Records,
+    player_name: String,
+    hint: Hint,
+    show_probabilities: bool,
+    probabilities: Option<HashMap<(u16, u16), f64>>,
+    probabilities_stale: bool,
+    replay_viewer: Option<ReplayViewer>,
+    replay_text: String,
+    replay_message: Option<String>,
+    board_text: String,
+    board_message: Option<String>,
+    key_bindings: KeyBindings,
+    /// Slot of a key binding which is waiting for a key to be pressed
+    key_binding_capture: Option<(KeyAction, usize)>,
+    /// Spot selected for keyboard play, shown once the player starts using the keyboard
+    cursor: Option<(u16, u16)>,
+    chording: Chording,
+    /// Size of the spots, in percent of their default size
+    zoom: u32,
+    /// Width the window needs for everything in the top panel to fit
+    toolbar_width: f32,
+    /// Dimensions and topology of the minefield, and zoom, which the window was last sized for
+    window_fit: Option<(u16, u16, Topology, u32)>,
+    /// Are both the primary and secondary buttons held down?
+    both_buttons_down: bool,
+    /// Was one of the primary and secondary buttons released in this frame, while both were held down?
+    both_buttons_released: bool,
+    /// Have both buttons been held down since the last time all buttons were released? Single clicks are ignored
+    /// until then, so that a chord doesn't also step on or flag a spot.
+    is_both_buttons_press: bool,
 }
 
 impl App for MinesweepRsApp {
@@ -33,22 +61,89 @@ impl App for MinesweepRsApp {
         ctx.request_repaint();
         ctx.set_debug_on_hover(false);
 
-        self.render_top_panel(ctx, frame);
-        self.render_bottom_panel(ctx, frame);
+        // Nobody is looking at the game
+        if !Self::has_focus(ctx) {
+            self.game.pause();
+        }
+
+        if let Some(viewer) = &mut self.replay_viewer {
+            viewer.advance(ctx.input().unstable_dt);
+
+            self.render_replay_panel(ctx, frame);
+        } else {
+            self.handle_shortcuts(ctx);
+
+            self.render_top_panel(ctx, frame);
+            self.render_bottom_panel(ctx, frame);
+        }
+
         self.render_toolbar_group(ctx, frame);
         self.render_minefield(ctx, frame);
+
+        self.handle_game_events();
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, Self::APP_NAME, &self.game_config);
+        eframe::set_value(storage, Self::KEY_BINDINGS_KEY, &self.key_bindings);
+        eframe::set_value(storage, Self::CHORDING_KEY, &self.chording);
+        eframe::set_value(storage, Self::ZOOM_KEY, &self.zoom);
+
+        // The terminal app may have recorded games of its own in the same file meanwhile
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(settings_file) = SettingsFile::open() {
+            self.records.merge_stored(&settings_file);
+        }
+        self.records.save(storage);
+        eframe::set_value(storage, Self::PLAYER_NAME_KEY, &self.player_name);
+        eframe::set_value(storage, Self::SAVED_GAME_KEY, &self.game.saved());
+    }
+
+    /// The web app only saves periodically (there is no chance to save when the page is closed), so save often enough
+    /// that the game in progress is not lost
+    #[cfg(target_arch = "wasm32")]
+    fn auto_save_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(2)
     }
 }
 
 impl MinesweepRsApp {
-    const APP_NAME: &str = "egui minesweep-rs";
+    /// Title of the native window, which also names the directory where the app data is stored
+    pub const TITLE: &str = "Egui Minesweep-Rs";
+    /// Storage key of the game config
+    pub const APP_NAME: &str = "egui minesweep-rs";
+    pub const PLAYER_NAME_KEY: &str = "player name";
+    pub const SAVED_GAME_KEY: &str = "saved game";
+    const KEY_BINDINGS_KEY: &str = "key bindings";
+    const CHORDING_KEY: &str = "chording";
+    const ZOOM_KEY: &str = "zoom";
+    const HISTOGRAM_BINS: usize = 10;
+    const RECENT_GAMES: usize = 10;
+    pub const DEFAULT_PLAYER_NAME: &str = "Player";
+    /// Size of a spot at 100% zoom
+    const SPOT_SIZE: f32 = 30.0;
+    /// Spots never shrink below this size to fit the window
+    const MIN_SPOT_SIZE: f32 = 12.0;
+    const MIN_ZOOM: u32 = 50;
+    const MAX_ZOOM: u32 = 200;
+    const DEFAULT_ZOOM: u32 = 100;
     const REFRESH_BTN_CHAR: &str = "🔄";
     const SETTINGS_BTN_CHAR: &str = "🛠";
     const ABOUT_BTN_CHAR: &str = "ℹ";
+    const HIGH_SCORES_BTN_CHAR: &str = "🏆";
+    const STATISTICS_BTN_CHAR: &str = "📊";
+    const HINT_BTN_CHAR: &str = "💡";
+    const HINT_SAFE_COLOR: Color32 = Color32::DARK_GREEN;
+    const HINT_MINE_COLOR: Color32 = Color32::DARK_RED;
+    const START_SPOT_COLOR: Color32 = Color32::DARK_BLUE;
+    const PROBABILITIES_BTN_CHAR: &str = "🔥";
+    const UNDO_BTN_CHAR: &str = "⮪";
+    const REDO_BTN_CHAR: &str = "⮫";
+    const REPLAYS_BTN_CHAR: &str = "🎞";
+    const SHARE_BTN_CHAR: &str = "🔗";
+    const REWIND_BTN_CHAR: &str = "⏮";
+    const PLAY_BTN_CHAR: &str = "⏵";
+    const PAUSE_BTN_CHAR: &str = "⏸";
     const MINE_CAHR: &str = "☢";
     const MINE_COLOR: Color32 = Color32::RED;
     const MINE_EXPLODED_CHAR: &str = "💥";
@@ -56,8 +151,7 @@ impl MinesweepRsApp {
     const FLAG_CHAR: &str = "⚐";
     const FLAG_COLOR_CORRECT: Color32 = Color32::GREEN;
     const FLAG_COLOR_WRONG: Color32 = Color32::RED;
-    const EMPTY_SPOT_CHARS: [&str; 9] = [" ", "1", "2", "3", "4", "5", "6", "7", "8"];
-    const EMPTY_SPOT_COLORS: [Color32; Self::EMPTY_SPOT_CHARS.len()] = [
+    const EMPTY_SPOT_COLORS: [Color32; 9] = [
         Color32::WHITE, Color32::WHITE, Color32::WHITE,
         Color32::WHITE, Color32::WHITE, Color32::WHITE,
         Color32::WHITE, Color32::WHITE, Color32::WHITE
@@ -69,40 +163,80 @@ impl MinesweepRsApp {
     const READY_COLOR: Color32 = Color32::GRAY;
     const FLAG_COUNT_OK_COLOR: Color32 = Color32::GRAY;
     const FLAG_COUNT_ERR_COLOR: Color32 = Color32::LIGHT_RED;
+    const CURSOR_COLOR: Color32 = Color32::YELLOW;
+    const WRAPPING_EDGE_COLOR: Color32 = Color32::LIGHT_BLUE;
 
     pub fn with_context(mut self, cc: &CreationContext) -> Self {
         if let Some(storage) = cc.storage {
             self.game_config = eframe::get_value(storage, Self::APP_NAME).unwrap_or_default();
             tracing::debug!("Loaded config from storage {:?}", self.game_config);
+
+            if !self.game_config.is_valid() {
+                tracing::warn!("Invalid config in storage {:?}. Using default config", self.game_config);
+                self.game_config = GameConfig::default();
+            }
+
+            self.key_bindings = eframe::get_value(storage, Self::KEY_BINDINGS_KEY).unwrap_or_default();
+            self.chording = eframe::get_value(storage, Self::CHORDING_KEY).unwrap_or_default();
+            self.zoom = eframe::get_value::<u32>(storage, Self::ZOOM_KEY)
+                .unwrap_or(Self::DEFAULT_ZOOM)
+                .clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+
+            self.records = Records::load(storage);
+            if let Some(player_name) = eframe::get_value(storage, Self::PLAYER_NAME_KEY) {
+                self.player_name = player_name;
+            }
+
+            self.refresh();
+
+            if let Some(saved_game) = eframe::get_value(storage, Self::SAVED_GAME_KEY) {
+                self.resume(saved_game);
+            }
         } else {
             tracing::debug!("No storage. Using default config {:?}", self.game_config);
+            self.refresh();
         }
 
-        self.minefield = Minefield::new(self.game_config.width, self.game_config.height).with_mines(self.game_config.mines);
-
         self
     }
     
-    #[allow(dead_code)]
-    pub fn with_configs(mut self, game_config: GameConfig) -> Self {
-        self.game_config = game_config;
-        self.minefield = Minefield::new(self.game_config.width, self.game_config.height).with_mines(self.game_config.mines);
+    /// Start with a game on the board of the given code, if the code is valid
+    pub fn with_board_code(mut self, code: &str) -> Self {
+        match Board::from_code(code) {
+            Ok(board) => self.load_board(board),
+            Err(e) => tracing::warn!("{}. Starting a new game", e),
+        }
 
         self
     }
 
-    fn render_top_panel(&mut self, ctx: &Context, _: &mut Frame) {
-        // Service app timer
-        while self.timer.poll().is_some() {
-            self.seconds_lapsed += 1;
+    pub fn with_configs(mut self, game_config: GameConfig) -> Self {
+        if game_config.is_valid() {
+            self.game_config = game_config;
+        } else {
+            tracing::warn!("Invalid config {:?}. Using default config", game_config);
+            self.game_config = GameConfig::default();
         }
+        self.refresh();
+
+        self
+    }
 
+    /// Config of the games started from now on
+    pub fn game_config(&self) -> GameConfig {
+        self.game_config
+    }
+
+    fn render_top_panel(&mut self, ctx: &Context, _: &mut Frame) {
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(10.);
+            // Room taken by the margins of the panel
+            let margins = ctx.input().screen_rect.width() - ui.available_width();
+
             egui::menu::bar(ui, |ui| {
 
                 // Config and game data
-                ui.with_layout(Layout::left_to_right(egui::Align::TOP), |ui| {
+                let game_data = ui.with_layout(Layout::left_to_right(egui::Align::TOP), |ui| {
                     // refresh btn
                     let refresh_btn = ui.add(
                         Button::new(
@@ -114,6 +248,67 @@ impl MinesweepRsApp {
                         self.refresh();
                     }
 
+                    // pause btn
+                    let pause_char = if self.game.is_paused() { Self::PLAY_BTN_CHAR } else { Self::PAUSE_BTN_CHAR };
+                    let pause_btn = ui.add_enabled(
+                        self.game.state() == GameState::Running,
+                        Button::new(
+                            RichText::new(pause_char).text_style(TextStyle::Heading),
+                        )
+                    ).on_hover_text("Pause the game, hiding the minefield");
+
+                    if pause_btn.clicked() {
+                        self.toggle_pause();
+                    }
+
+                    // hint btn, only useful once there is something revealed to reason about, and the solver only knows
+                    // about spots holding a single mine
+                    let hint_btn = ui.add_enabled(
+                        self.game.state() == GameState::Running && !self.game.is_paused() && self.game.minefield().mines() > 0 &&
+                        self.game.config().mines_per_spot == 1,
+                        Button::new(
+                            RichText::new(Self::HINT_BTN_CHAR).text_style(TextStyle::Heading),
+                        )
+                    ).on_hover_text("Show a spot which can be deduced from what is visible");
+
+                    if hint_btn.clicked() {
+                        self.show_hint();
+                    }
+
+                    // mine probability overlay toggle, which would make ranked games too easy
+                    let probabilities_btn = ui.add_enabled(
+                        self.game.config().play_mode == PlayMode::Practice && self.game.config().mines_per_spot == 1,
+                        SelectableLabel::new(
+                            self.show_probabilities,
+                            RichText::new(Self::PROBABILITIES_BTN_CHAR).text_style(TextStyle::Heading),
+                        )
+                    )
+                    .on_hover_text("Show the probability of each hidden spot being a mine")
+                    .on_disabled_hover_text("Mine probabilities are only available in practice games with one mine per spot");
+
+                    if probabilities_btn.clicked() {
+                        self.show_probabilities = !self.show_probabilities;
+                    }
+
+                    // undo and redo btns
+                    let undo_btn = ui.add_enabled(
+                        self.game.can_undo(),
+                        Button::new(RichText::new(Self::UNDO_BTN_CHAR).text_style(TextStyle::Heading))
+                    ).on_hover_text("Undo (Ctrl+Z). Games with undone moves don't enter the high scores");
+
+                    if undo_btn.clicked() {
+                        self.game.undo();
+                    }
+
+                    let redo_btn = ui.add_enabled(
+                        self.game.can_redo(),
+                        Button::new(RichText::new(Self::REDO_BTN_CHAR).text_style(TextStyle::Heading))
+                    ).on_hover_text("Redo (Ctrl+Y)");
+
+                    if redo_btn.clicked() {
+                        self.game.redo();
+                    }
+
                     ui.separator();
 
                     ui.allocate_ui_with_layout(Vec2::new(10.0, 10.0), Layout::left_to_right(egui::Align::Center), |ui| {
@@ -123,7 +318,7 @@ impl MinesweepRsApp {
                         ));
                         ui.add(
                             Label::new(
-                            RichText::new(format!("{}", self.minefield.mines())).monospace().text_style(TextStyle::Heading)
+                            RichText::new(format!("{}", self.game.config().mines)).monospace().text_style(TextStyle::Heading)
                         ));
                     });
 
@@ -135,10 +330,10 @@ impl MinesweepRsApp {
                             RichText::new("Flags").text_style(TextStyle::Body)
                         ));
 
-                        let flag_count_color = if self.minefield.mines() >= self.placed_flags { Self::FLAG_COUNT_OK_COLOR } else { Self::FLAG_COUNT_ERR_COLOR };
+                        let flag_count_color = if self.game.config().mines >= self.game.placed_flags() { Self::FLAG_COUNT_OK_COLOR } else { Self::FLAG_COUNT_ERR_COLOR };
                         ui.add(
                             Label::new(
-                                RichText::new(format!("{}", self.placed_flags))
+                                RichText::new(format!("{}", self.game.placed_flags()))
                                 .color(flag_count_color)
                                 .monospace()
                                 .text_style(TextStyle::Heading)
@@ -154,7 +349,7 @@ impl MinesweepRsApp {
                         ));
                         ui.add(
                             Label::new(
-                            RichText::new(format!("{}", self.seconds_lapsed)).monospace().text_style(TextStyle::Heading)
+                            RichText::new(self.time_text()).monospace().text_style(TextStyle::Heading)
                         ));
                     });
 
@@ -162,7 +357,7 @@ impl MinesweepRsApp {
                 });
 
                 // controls
-                ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
+                let controls = ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
 
                     // settings button
                     if ui.add(
@@ -170,10 +365,58 @@ impl MinesweepRsApp {
                             RichText::new(Self::SETTINGS_BTN_CHAR).text_style(TextStyle::Heading),
                         )
                     ).clicked() {
-                        if let UiToolbarGroup::Settings(_) = self.ui_toolbar_group {
+                        self.toggle_settings();
+                    }
+
+                    // high scores button
+                    if ui.add(
+                        Button::new(
+                            RichText::new(Self::HIGH_SCORES_BTN_CHAR).text_style(TextStyle::Heading)
+                        )
+                    ).clicked() {
+                        if let UiToolbarGroup::HighScores(_) = self.ui_toolbar_group {
+                            self.ui_toolbar_group = UiToolbarGroup::None;
+                        } else {
+                            self.ui_toolbar_group = UiToolbarGroup::HighScores(self.game_config.ranked());
+                        }
+                    }
+
+                    // statistics button
+                    if ui.add(
+                        Button::new(
+                            RichText::new(Self::STATISTICS_BTN_CHAR).text_style(TextStyle::Heading)
+                        )
+                    ).clicked() {
+                        if let UiToolbarGroup::Statistics(_) = self.ui_toolbar_group {
+                            self.ui_toolbar_group = UiToolbarGroup::None;
+                        } else {
+                            self.ui_toolbar_group = UiToolbarGroup::Statistics(self.game_config.ranked());
+                        }
+                    }
+
+                    // replays button
+                    if ui.add(
+                        Button::new(
+                            RichText::new(Self::REPLAYS_BTN_CHAR).text_style(TextStyle::Heading)
+                        )
+                    ).clicked() {
+                        if let UiToolbarGroup::Replays = self.ui_toolbar_group {
                             self.ui_toolbar_group = UiToolbarGroup::None;
                         } else {
-                            self.ui_toolbar_group = UiToolbarGroup::Settings(self.game_config);
+                            self.ui_toolbar_group = UiToolbarGroup::Replays;
+                        }
+                    }
+
+                    // share button
+                    if ui.add(
+                        Button::new(
+                            RichText::new(Self::SHARE_BTN_CHAR).text_style(TextStyle::Heading)
+                        )
+                    ).clicked() {
+                        if let UiToolbarGroup::Share = self.ui_toolbar_group {
+                            self.ui_toolbar_group = UiToolbarGroup::None;
+                        } else {
+                            self.ui_toolbar_group = UiToolbarGroup::Share;
                         }
                     }
 
@@ -190,11 +433,117 @@ impl MinesweepRsApp {
                         }
                     }
                 });
+
+                self.toolbar_width = margins + game_data.response.rect.width() + ui.spacing().item_spacing.x +
+                    controls.response.rect.width();
             });
             ui.add_space(10.);
         });
     }
 
+    /// One row for each `KeyAction`, with a button for each of its two keys. Click a button, then press a key to bind
+    /// it. Right-click a button to unbind its key.
+    fn render_key_bindings(&mut self, ui: &mut Ui) {
+        Grid::new("key_bindings").striped(true).show(ui, |ui| {
+            for action in KeyAction::ALL {
+                ui.add(Label::new(format!("{:?}", action)));
+
+                for slot in 0..2 {
+                    let text = if self.key_binding_capture == Some((action, slot)) {
+                        "Press a key...".to_owned()
+                    } else {
+                        match self.key_bindings.keys(action)[slot] {
+                            Some(key) => format!("{:?}", key),
+                            None => "-".to_owned(),
+                        }
+                    };
+
+                    let key_btn = ui.add(Button::new(text));
+                    if key_btn.clicked_by(PointerButton::Primary) {
+                        self.key_binding_capture = Some((action, slot));
+                    }
+                    if key_btn.clicked_by(PointerButton::Secondary) {
+                        self.key_bindings.bind(action, slot, None);
+                        self.key_binding_capture = None;
+                    }
+                }
+
+                ui.end_row();
+            }
+        });
+
+        if ui.button("Defaults").clicked() {
+            self.key_bindings = KeyBindings::default();
+            self.key_binding_capture = None;
+        }
+    }
+
+    /// Playback controls, shown instead of the top panel while a replay is watched
+    fn render_replay_panel(&mut self, ctx: &Context, _: &mut Frame) {
+        let mut close = false;
+
+        if let Some(viewer) = &mut self.replay_viewer {
+            TopBottomPanel::top("replay_panel").show(ctx, |ui| {
+                ui.add_space(10.);
+                egui::menu::bar(ui, |ui| {
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        if ui.add(
+                            Button::new(RichText::new(Self::REWIND_BTN_CHAR).text_style(TextStyle::Heading))
+                        ).on_hover_text("Back to the start").clicked() {
+                            viewer.rewind();
+                        }
+
+                        let play_pause_char = if viewer.is_playing { Self::PAUSE_BTN_CHAR } else { Self::PLAY_BTN_CHAR };
+                        if ui.add(
+                            Button::new(RichText::new(play_pause_char).text_style(TextStyle::Heading))
+                        ).clicked() {
+                            if viewer.is_finished() {
+                                viewer.rewind();
+                            }
+                            viewer.is_playing = !viewer.is_playing;
+                        }
+
+                        ui.separator();
+
+                        ui.add(Slider::new(&mut viewer.speed, 0.25..=8.0).logarithmic(true).text("Speed"));
+
+                        ui.separator();
+
+                        ui.add(
+                            Label::new(
+                            RichText::new(format!(
+                                "{:.1} / {:.1}",
+                                viewer.position.min(viewer.replay.duration_millis() as f64) / 1000.0,
+                                viewer.replay.duration_millis() as f64 / 1000.0,
+                            )).monospace().text_style(TextStyle::Heading)
+                        ));
+
+                        ui.separator();
+
+                        let (result_text, result_color) = match (viewer.is_finished(), viewer.replay.is_won) {
+                            (false, _) => ("Replay", Self::READY_COLOR),
+                            (true, true) => ("Won", Self::WON_COLOR),
+                            (true, false) => ("Lost", Self::LOST_COLOR),
+                        };
+                        ui.add(Label::new(RichText::new(result_text).color(result_color)));
+                        ui.add(Label::new(format!("{}, {}", viewer.replay.player, viewer.replay.date)));
+                    });
+
+                    ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+                ui.add_space(10.);
+            });
+        }
+
+        if close {
+            self.replay_viewer = None;
+        }
+    }
+
     fn render_toolbar_group(&mut self, ctx: &Context, _: &mut Frame) {
         let mut open = true;
 
@@ -216,9 +565,8 @@ impl MinesweepRsApp {
             },
 
             // Settings window
-            UiToolbarGroup::Settings(mut game_config) => {
+            UiToolbarGroup::Settings(mut game_config, currently_selected) => {
                 Window::new("Settings").open(&mut open).show(ctx, |ui| {
-                    let currently_selected = GameDifficulty::from_config(&game_config);
                     let mut selected = currently_selected;
                     
                     ComboBox::from_label("Game difficulty")
@@ -227,6 +575,7 @@ impl MinesweepRsApp {
                             ui.selectable_value(&mut selected, GameDifficulty::Easy, "Easy");
                             ui.selectable_value(&mut selected, GameDifficulty::Medium, "Medium");
                             ui.selectable_value(&mut selected, GameDifficulty::Hard, "Hard");
+                            ui.selectable_value(&mut selected, GameDifficulty::Custom, "Custom");
                         }
                     );
 
@@ -235,263 +584,1445 @@ impl MinesweepRsApp {
 
                         match selected {
                             GameDifficulty::Easy => {
-                                game_config = GameDifficulty::EASY;
+                                game_config = game_config.with_board_of(&GameDifficulty::EASY);
                             },
                             GameDifficulty::Medium => {
-                                game_config = GameDifficulty::MEDIUM;
+                                game_config = game_config.with_board_of(&GameDifficulty::MEDIUM);
                             },
                             GameDifficulty::Hard => {
-                                game_config = GameDifficulty::HARD;
+                                game_config = game_config.with_board_of(&GameDifficulty::HARD);
+                            },
+                            GameDifficulty::Custom => {
+                                // Start customizing from whatever was selected before
                             },
                         }
 
-                        // Save the new config into the toolbar window variant (don't apply yet!)
-                        self.ui_toolbar_group = UiToolbarGroup::Settings(game_config);
                         tracing::debug!("\tnew: {:?} {:?}", selected, game_config);
                     }
 
-                    ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        if ui.button("Apply").clicked_by(PointerButton::Primary) {
-                            tracing::debug!("\tapply: {:?}", game_config);
-                            self.game_config = game_config;
-                            self.refresh();
-                        }
+                    if selected == GameDifficulty::Custom {
+                        ui.separator();
 
-                        if ui.button("Cancel").clicked_by(PointerButton::Primary) {
-                            self.ui_toolbar_group = UiToolbarGroup::None;
-                        }                        
-                    });
-                });                
-            },
+                        ui.add(
+                            Slider::new(&mut game_config.width, GameConfig::MIN_WIDTH..=GameConfig::MAX_WIDTH)
+                            .text("Width")
+                        );
+                        ui.add(
+                            Slider::new(&mut game_config.height, GameConfig::MIN_HEIGHT..=GameConfig::MAX_HEIGHT)
+                            .text("Height")
+                        );
 
-            UiToolbarGroup::None => {},
-        }
+                        // Shrinking the field may leave too many mines in it
+                        let max_mines = game_config.max_mines();
+                        game_config.mines = game_config.mines.clamp(GameConfig::MIN_MINES, max_mines);
+                        ui.add(
+                            Slider::new(&mut game_config.mines, GameConfig::MIN_MINES..=max_mines)
+                            .text("Mines")
+                        );
+                    }
 
-        // If the user closes whatever group window was open, make sure it's not rendered anymore
-        if !open {
-            self.ui_toolbar_group = UiToolbarGroup::None;
-        }
-    }
+                    ui.separator();
 
-    fn render_bottom_panel(&mut self, ctx: &Context, _: &mut Frame) {
-        // define a TopBottomPanel widget
-        TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                match self.game_state {
-                    GameState::Ready => {
-                        ui.add(Label::new(
-                            RichText::new("Ready")
-                                .small()
-                                .color(Self::READY_COLOR)
-                                .text_style(TextStyle::Monospace),
-                        ));
-                    },
-                    GameState::Running => {
-                    },
-                    GameState::Stopped(is_won) => {
-                        if is_won {
-                            ui.add(Label::new(
-                                RichText::new("You WIN!")
-                                    .color(Self::WON_COLOR)
-                                    .text_style(TextStyle::Monospace),
-                            ));
-                        } else {
-                            ui.add(Label::new(
-                                RichText::new("You lost.")
-                                    .color(Self::LOST_COLOR)
-                                    .text_style(TextStyle::Monospace),
-                            ));
+                    ComboBox::from_label("Topology")
+                        .selected_text(format!("{:?}", game_config.topology))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut game_config.topology, Topology::Square, "Square")
+                                .on_hover_text("Square spots, each with 8 neighbors");
+                            ui.selectable_value(&mut game_config.topology, Topology::Hex, "Hex")
+                                .on_hover_text("Hexagonal spots, each with 6 neighbors");
+                            ui.selectable_value(&mut game_config.topology, Topology::Torus, "Torus")
+                                .on_hover_text("Square spots, with the edges wrapping around to the opposite edges, so even corners have 8 neighbors");
                         }
-                    },
-                }
-            })
-        });
-    }
+                    );
 
-    fn render_minefield(&mut self, ctx: &Context, _: &mut Frame) {
-        CentralPanel::default().show(ctx, |ui| {
+                    ui.add(
+                        Slider::new(&mut game_config.mines_per_spot, 1..=GameConfig::MAX_MINES_PER_SPOT)
+                        .text("Mines per spot")
+                    ).on_hover_text("Spots can hold more than one mine, and take as many flags as they have mines");
 
-            let size = 30.0;
-            TableBuilder::new(ui)
-                .cell_layout(Layout::centered_and_justified(Direction::LeftToRight))
-                .columns(Size::Absolute { initial: size - 1.0, range: (size - 1.0, size - 1.0) }, self.minefield.width() as usize)
-                .body(|mut body| {
-                    for y in 0..self.minefield.height() {
-                        body.row(size + 2.0, |mut row| {
-                            for x in 0..self.minefield.width() {
-                                row.col(|ui| {
-                                    self.render_spot(x, y, size, ui);
-                                });
+                    // There may be more mines than before, or fewer
+                    game_config.mines = game_config.mines.min(game_config.max_mines());
+
+                    ComboBox::from_label("Seed")
+                        .selected_text(match game_config.seed {
+                            Seed::Random => "Random",
+                            Seed::Fixed(_) => "Fixed",
+                            Seed::Daily => "Daily",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut game_config.seed, Seed::Random, "Random")
+                                .on_hover_text("Every game is played on a new minefield");
+                            // Start from the current game's seed, which is short enough to be shared. A game with a
+                            // random seed starts wherever the player clicks first, so its minefield is not the same.
+                            if ui.selectable_label(matches!(game_config.seed, Seed::Fixed(_)), "Fixed")
+                                .on_hover_text("Every game is played on the minefield of the chosen seed")
+                                .clicked() && !matches!(game_config.seed, Seed::Fixed(_)) {
+                                game_config.seed = Seed::Fixed(self.game.seed());
                             }
+                            ui.selectable_value(&mut game_config.seed, Seed::Daily, "Daily")
+                                .on_hover_text("Everyone plays the same minefield today, with its own high scores");
+                        }
+                    );
+
+                    if let Seed::Fixed(seed) = &mut game_config.seed {
+                        ui.horizontal(|ui| {
+                            ui.add(DragValue::new(seed).speed(0.0));
+                            ui.label("Seed number");
                         });
                     }
-                }
-            );
-            
-        });
-    }
 
-    /// Render one spot/tile at the given field coordinates
-    fn render_spot(&mut self, x: u16, y: u16, size: f32, ui: &mut Ui) {
-        let spot = self.minefield.spot(x, y).unwrap();
+                    ComboBox::from_label("First click")
+                        .selected_text(format!("{:?}", game_config.first_click))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut game_config.first_click, FirstClick::Safe, "Safe")
+                                .on_hover_text("The first click never hits a mine");
+                            ui.selectable_value(&mut game_config.first_click, FirstClick::Opening, "Opening")
+                                .on_hover_text("The first click always opens an area with no neighboring mines");
+                        }
+                    );
 
-        match self.game_state {
-            GameState::Ready | GameState::Running => {
-                match spot.state {
-                    SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine => {
-                        let hidden_btn = Button::new(
-                            RichText::new(Self::HIDDEN_SPOT_CHAR)
-                            .color(Self::HIDDEN_SPOT_COLOR)
-                            .monospace()
-                            .size(size)
-                        );
-                        let hidden_btn = ui.add_enabled(true, hidden_btn);
+                    ComboBox::from_label("Play mode")
+                        .selected_text(format!("{:?}", game_config.play_mode))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut game_config.play_mode, PlayMode::Ranked, "Ranked")
+                                .on_hover_text("Won games enter the high scores");
+                            ui.selectable_value(&mut game_config.play_mode, PlayMode::Practice, "Practice")
+                                .on_hover_text("Won games never enter the high scores, but mine probabilities can be shown");
+                        }
+                    );
 
-                        if hidden_btn.clicked_by(PointerButton::Primary) {
-                            self.check_ready_to_running();
+                    // The solver only knows about spots holding a single mine
+                    if game_config.mines_per_spot > 1 {
+                        game_config.generator = Generator::Random;
+                    }
 
-                            if self.minefield.step(x, y) == StepResult::Boom {
-                                self.game_over(false);
-                            } else if self.minefield.is_cleared() {
-                                self.game_over(true);
+                    ui.add_enabled_ui(game_config.mines_per_spot == 1, |ui| {
+                        ComboBox::from_label("Minefield generator")
+                            .selected_text(format!("{:?}", game_config.generator))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut game_config.generator, Generator::Random, "Random")
+                                    .on_hover_text("Mines are placed at random, so some games need a lucky guess");
+                                ui.selectable_value(&mut game_config.generator, Generator::NoGuess, "NoGuess")
+                                    .on_hover_text("Every game can be won by pure logic, starting with an opening");
                             }
-                        }
+                        );
+                    });
 
-                        if hidden_btn.clicked_by(PointerButton::Secondary) {
-                            self.check_ready_to_running();
-                            
-                            match self.minefield.toggle_flag(x, y) {
-                                FlagToggleResult::Removed => self.placed_flags -= 1,
-                                FlagToggleResult::Added => self.placed_flags += 1,
-                                FlagToggleResult::None => {},
-                            }
-                            
-                            if self.minefield.is_cleared() {
-                                self.game_over(true);
+                    // Controls take effect right away
+                    ui.separator();
+                    CollapsingHeader::new("Controls").show(ui, |ui| {
+                        ComboBox::from_label("Chording")
+                            .selected_text(format!("{:?}", self.chording))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.chording, Chording::MiddleButton, "MiddleButton")
+                                    .on_hover_text("Click a number with the middle button to step on its neighbors");
+                                ui.selectable_value(&mut self.chording, Chording::BothButtons, "BothButtons")
+                                    .on_hover_text("Press both buttons on a number, then release them, to step on its neighbors");
+                                ui.selectable_value(&mut self.chording, Chording::PrimaryButton, "PrimaryButton")
+                                    .on_hover_text("Click a number to step on its neighbors");
                             }
-                        }
-                    },
-                    SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine => {
-                        let flag_btn = Button::new(
-                            RichText::new(Self::FLAG_CHAR)
-                            .color(Self::FLAG_COLOR_CORRECT)
-                            .monospace()
-                            .size(size)
                         );
-                        let flag_btn = ui.add_enabled(true, flag_btn);
 
-                        if flag_btn.clicked_by(PointerButton::Secondary) {
-                            match self.minefield.toggle_flag(x, y) {
-                                FlagToggleResult::Removed => self.placed_flags -= 1,
-                                FlagToggleResult::Added => self.placed_flags += 1,
-                                FlagToggleResult::None => {},
-                            }
+                        ui.separator();
 
-                            if self.minefield.is_cleared() {
-                                self.game_over(true);
-                            }
-                        }
-                    },
+                        self.render_key_bindings(ui);
+                    });
 
-                    SpotState::RevealedEmpty { neighboring_mines } => {
-                        let empty_lbl = Label::new(
-                            RichText::new(Self::EMPTY_SPOT_CHARS[neighboring_mines as usize])
-                            .color(Self::EMPTY_SPOT_COLORS[neighboring_mines as usize])
-                            .monospace()
-                            .size(size)
-                        );
+                    CollapsingHeader::new("Display").show(ui, |ui| {
+                        ui.add(
+                            Slider::new(&mut self.zoom, Self::MIN_ZOOM..=Self::MAX_ZOOM)
+                            .step_by(10.0)
+                            .suffix("%")
+                            .text("Zoom")
+                        ).on_hover_text("Size of the spots. They shrink when the minefield doesn't fit in the window");
+                    });
 
-                        let empty_lbl = ui.add_enabled(true, empty_lbl.sense(Sense::click()));
+                    // Save the new config into the toolbar window variant (don't apply yet!)
+                    self.ui_toolbar_group = UiToolbarGroup::Settings(game_config, selected);
 
-                        if empty_lbl.clicked_by(PointerButton::Middle) {
-                            self.check_ready_to_running();
+                    let is_valid = game_config.is_valid();
 
-                            if self.minefield.auto_step(x, y) == StepResult::Boom {
-                                self.game_over(false);
-                            } else if self.minefield.is_cleared() {
-                                self.game_over(true);
-                            }
+                    if !is_valid {
+                        ui.add(Label::new(
+                            RichText::new(format!(
+                                "The minefield must be between {}x{} and {}x{}, with at least {} mine and at least one empty spot",
+                                GameConfig::MIN_WIDTH, GameConfig::MIN_HEIGHT,
+                                GameConfig::MAX_WIDTH, GameConfig::MAX_HEIGHT,
+                                GameConfig::MIN_MINES,
+                            ))
+                            .color(Self::FLAG_COUNT_ERR_COLOR)
+                        ));
+                    }
+
+                    ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
+                        if ui.add_enabled(is_valid, Button::new("Apply")).clicked_by(PointerButton::Primary) {
+                            tracing::debug!("\tapply: {:?}", game_config);
+                            self.game_config = game_config;
+                            self.refresh();
                         }
-                    },
-                    SpotState::ExplodedMine => {
-                        unreachable!()
-                    },
-                }
-            },
 
-            GameState::Stopped(is_won) => {
-                match spot.state {
-                    SpotState::HiddenEmpty { neighboring_mines: _ } => {
-                        let _ = ui.add_enabled(false, Button::new(
-                            RichText::new(Self::HIDDEN_SPOT_CHAR)
-                            .color(Self::HIDDEN_SPOT_COLOR)
-                            .monospace()
-                            .size(size)
-                        ));                        
+                        if ui.button("Cancel").clicked_by(PointerButton::Primary) {
+                            self.ui_toolbar_group = UiToolbarGroup::None;
+                        }                        
+                    });
+                });                
+            },
+
+            // High scores window
+            UiToolbarGroup::HighScores(game_config) => {
+                Window::new("High scores").open(&mut open).show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Player name");
+                        ui.add(TextEdit::singleline(&mut self.player_name).desired_width(120.0));
+                    });
+
+                    ui.separator();
+
+                    let mut selected = game_config;
+
+                    ComboBox::from_label("Game difficulty")
+                        .selected_text(Self::config_name(&selected))
+                        .show_ui(ui, |ui| {
+                            let presets = [GameDifficulty::EASY, GameDifficulty::MEDIUM, GameDifficulty::HARD]
+                                .map(|preset| self.game_config.ranked().with_board_of(&preset));
+
+                            for config in presets {
+                                ui.selectable_value(&mut selected, config, Self::config_name(&config));
+                            }
+
+                            for config in self.records.high_scores.configs().chain(self.records.daily_high_scores.configs(Seed::today())) {
+                                if !presets.contains(config) {
+                                    ui.selectable_value(&mut selected, *config, Self::config_name(config));
+                                }
+                            }
+                        }
+                    );
+
+                    if selected != game_config {
+                        self.ui_toolbar_group = UiToolbarGroup::HighScores(selected);
+                    }
+
+                    ui.separator();
+
+                    let is_daily = selected.seed == Seed::Daily;
+                    if is_daily {
+                        ui.label(format!("Daily challenge of {}", Seed::date_of(Seed::today())));
+                    }
+
+                    let scores = if is_daily {
+                        self.records.daily_high_scores.scores(Seed::today(), &selected)
+                    } else {
+                        self.records.high_scores.scores(&selected)
+                    };
+                    let has_scores = !scores.is_empty();
+
+                    if !has_scores {
+                        ui.add(Label::new(RichText::new("No games won yet").color(Self::READY_COLOR)));
+                    } else {
+                        Grid::new("high_scores_grid").striped(true).show(ui, |ui| {
+                            ui.label(RichText::new("#").strong());
+                            ui.label(RichText::new("Name").strong());
+                            ui.label(RichText::new("Time").strong());
+                            ui.label(RichText::new("Date").strong());
+                            ui.label(RichText::new("Hints").strong());
+                            ui.end_row();
+
+                            for (rank, score) in scores.iter().enumerate() {
+                                ui.label(format!("{}", rank + 1));
+                                ui.label(&score.name);
+                                ui.label(RichText::new(format!("{:.3}", score.millis as f64 / 1000.0)).monospace());
+                                ui.label(&score.date);
+                                if score.is_assisted() {
+                                    ui.label(format!("{}", score.hints));
+                                } else {
+                                    ui.label("");
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    }
+
+                    ui.separator();
+
+                    ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
+                        if ui.button("Clear all").clicked_by(PointerButton::Primary) {
+                            if is_daily {
+                                self.records.daily_high_scores.clear_all();
+                            } else {
+                                self.records.high_scores.clear_all();
+                            }
+                        }
+
+                        if ui.add_enabled(has_scores, Button::new("Clear")).clicked_by(PointerButton::Primary) {
+                            if is_daily {
+                                self.records.daily_high_scores.clear(&selected);
+                            } else {
+                                self.records.high_scores.clear(&selected);
+                            }
+                        }
+                    });
+                });
+            },
+
+            // Statistics window
+            UiToolbarGroup::Statistics(game_config) => {
+                Window::new("Statistics").open(&mut open).show(ctx, |ui| {
+                    let mut selected = game_config;
+
+                    ComboBox::from_label("Game difficulty")
+                        .selected_text(Self::config_name(&selected))
+                        .show_ui(ui, |ui| {
+                            let presets = [GameDifficulty::EASY, GameDifficulty::MEDIUM, GameDifficulty::HARD]
+                                .map(|preset| self.game_config.ranked().with_board_of(&preset));
+
+                            for config in presets {
+                                ui.selectable_value(&mut selected, config, Self::config_name(&config));
+                            }
+
+                            for config in self.records.statistics.configs() {
+                                if !presets.contains(config) {
+                                    ui.selectable_value(&mut selected, *config, Self::config_name(config));
+                                }
+                            }
+                        }
+                    );
+
+                    if selected != game_config {
+                        self.ui_toolbar_group = UiToolbarGroup::Statistics(selected);
+                    }
+
+                    ui.separator();
+
+                    let table = self.records.statistics.table(&selected);
+                    let has_games = table.is_some();
+
+                    if let Some(table) = table {
+                        Grid::new("statistics_grid").show(ui, |ui| {
+                            ui.label("Games played");
+                            ui.label(RichText::new(format!("{}", table.played)).monospace());
+                            ui.end_row();
+
+                            ui.label("Games won");
+                            ui.label(RichText::new(format!("{} ({:.1}%)", table.won, table.win_percentage())).monospace());
+                            ui.end_row();
+
+                            ui.label("Current streak");
+                            ui.label(RichText::new(format!("{}", table.current_streak)).monospace());
+                            ui.end_row();
+
+                            ui.label("Best streak");
+                            ui.label(RichText::new(format!("{}", table.best_streak)).monospace());
+                            ui.end_row();
+                        });
+
+                        ui.separator();
+
+                        ui.label("Win times (seconds)");
+                        let (bin_width, histogram) = table.win_times_histogram(Self::HISTOGRAM_BINS);
+                        let bars = histogram
+                            .iter()
+                            .enumerate()
+                            .map(|(bin, wins)| {
+                                Bar::new((bin as f64 + 0.5) * bin_width, *wins as f64)
+                                    .width(bin_width)
+                                    .name(format!("{}s to {}s", bin as f64 * bin_width, (bin + 1) as f64 * bin_width))
+                            })
+                            .collect();
+                        Plot::new("win_times_plot")
+                            .height(150.0)
+                            .width(300.0)
+                            .allow_drag(false)
+                            .allow_zoom(false)
+                            .allow_scroll(false)
+                            .allow_boxed_zoom(false)
+                            .include_y(0.0)
+                            .show(ui, |plot_ui| {
+                                plot_ui.bar_chart(BarChart::new(bars).color(Self::WON_COLOR));
+                            });
+
+                        ui.separator();
+
+                        Grid::new("recent_games_grid").striped(true).show(ui, |ui| {
+                            ui.label(RichText::new("Date").strong());
+                            ui.label(RichText::new("Result").strong());
+                            ui.label(RichText::new("Time").strong());
+                            ui.label(RichText::new("Clicks").strong());
+                            ui.label(RichText::new("3BV").strong());
+                            ui.end_row();
+
+                            for game in table.games.iter().rev().take(Self::RECENT_GAMES) {
+                                ui.label(&game.date);
+                                if game.is_won {
+                                    ui.label(RichText::new("Won").color(Self::WON_COLOR));
+                                } else {
+                                    ui.label(RichText::new("Lost").color(Self::LOST_COLOR));
+                                }
+                                ui.label(RichText::new(format!("{:.3}", game.millis as f64 / 1000.0)).monospace());
+                                ui.label(RichText::new(format!("{}", game.clicks)).monospace());
+                                ui.label(RichText::new(format!("{}", game.bbbv)).monospace());
+                                ui.end_row();
+                            }
+                        });
+                    } else {
+                        ui.add(Label::new(RichText::new("No games played yet").color(Self::READY_COLOR)));
+                    }
+
+                    ui.separator();
+
+                    ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
+                        if ui.button("Clear all").clicked_by(PointerButton::Primary) {
+                            self.records.statistics.clear_all();
+                        }
+
+                        if ui.add_enabled(has_games, Button::new("Clear")).clicked_by(PointerButton::Primary) {
+                            self.records.statistics.clear(&selected);
+                        }
+                    });
+                });
+            },
+
+            // Replays window
+            UiToolbarGroup::Replays => {
+                Window::new("Replays").open(&mut open).show(ctx, |ui| {
+                    let mut watch = None;
+
+                    if self.records.replays.is_empty() {
+                        ui.add(Label::new(RichText::new("No games played yet").color(Self::READY_COLOR)));
+                    } else {
+                        Grid::new("replays_grid").striped(true).show(ui, |ui| {
+                            for replay in &self.records.replays {
+                                ui.label(&replay.date);
+                                ui.label(Self::config_name(&replay.config));
+                                if replay.is_won {
+                                    ui.label(RichText::new("Won").color(Self::WON_COLOR));
+                                } else {
+                                    ui.label(RichText::new("Lost").color(Self::LOST_COLOR));
+                                }
+                                ui.label(RichText::new(format!("{:.3}", replay.duration_millis() as f64 / 1000.0)).monospace());
+
+                                if ui.button("Watch").clicked() {
+                                    watch = Some(replay.clone());
+                                }
+
+                                if ui.button("Copy").on_hover_text("Copy the replay to the clipboard, as text").clicked() {
+                                    ui.output().copied_text = replay.to_text();
+                                    self.replay_message = Some("Replay copied to the clipboard".to_owned());
+                                }
+
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if ui.button("Save").on_hover_text("Save the replay to a file in the current directory").clicked() {
+                                    let file_name = format!("minesweep-rs-{}.replay", replay.date.replace([' ', ':'], "-"));
+                                    self.replay_message = Some(match std::fs::write(&file_name, replay.to_text()) {
+                                        Ok(_) => format!("Replay saved to {}", file_name),
+                                        Err(e) => format!("Could not save {}: {}", file_name, e),
+                                    });
+                                }
+
+                                ui.end_row();
+                            }
+                        });
+                    }
+
+                    ui.separator();
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let hint_text = "Paste a replay, or the path of a replay file";
+                    #[cfg(target_arch = "wasm32")]
+                    let hint_text = "Paste a replay";
+
+                    ui.add(TextEdit::singleline(&mut self.replay_text).hint_text(hint_text).desired_width(f32::INFINITY));
+
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!self.replay_text.is_empty(), Button::new("Watch")).clicked() {
+                            match Self::load_replay(&self.replay_text) {
+                                Ok(replay) => watch = Some(replay),
+                                Err(e) => self.replay_message = Some(e),
+                            }
+                        }
+
+                        if let Some(replay_message) = &self.replay_message {
+                            ui.label(replay_message);
+                        }
+                    });
+
+                    if let Some(replay) = watch {
+                        self.game.pause();
+                        self.replay_message = None;
+                        self.replay_viewer = Some(ReplayViewer::new(replay));
+                    }
+                });
+            },
+
+            // Share window
+            UiToolbarGroup::Share => {
+                Window::new("Share board").open(&mut open).show(ctx, |ui| {
+                    let board_code = self.game.board().map(|board| board.to_code());
+
+                    ui.horizontal(|ui| {
+                        let copy_btn = ui.add_enabled(board_code.is_some(), Button::new("Copy board code"))
+                            .on_hover_text("Copy a code of this minefield to the clipboard, so that others can play it")
+                            .on_disabled_hover_text("A ranked game can be shared once it is over");
+
+                        if let (true, Some(board_code)) = (copy_btn.clicked(), &board_code) {
+                            ui.output().copied_text = board_code.clone();
+                            self.board_message = Some("Board code copied to the clipboard".to_owned());
+                        }
+
+                        #[cfg(target_arch = "wasm32")]
+                        {
+                            let copy_link_btn = ui.add_enabled(board_code.is_some(), Button::new("Copy link"))
+                                .on_hover_text("Copy a link which opens this minefield to the clipboard");
+
+                            if let (true, Some(board_code)) = (copy_link_btn.clicked(), &board_code) {
+                                if let Some(link) = Self::board_link(board_code) {
+                                    ui.output().copied_text = link;
+                                    self.board_message = Some("Link copied to the clipboard".to_owned());
+                                }
+                            }
+                        }
+                    });
+
+                    ui.separator();
+
+                    ui.add(TextEdit::singleline(&mut self.board_text).hint_text("Paste a board code").desired_width(f32::INFINITY));
+
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!self.board_text.is_empty(), Button::new("Load board code")).clicked() {
+                            match Board::from_code(&self.board_text) {
+                                Ok(board) => self.load_board(board),
+                                Err(e) => self.board_message = Some(e),
+                            }
+                        }
+
+                        if let Some(board_message) = &self.board_message {
+                            ui.label(board_message);
+                        }
+                    });
+                });
+            },
+
+            UiToolbarGroup::None => {},
+        }
+
+        // If the user closes whatever group window was open, make sure it's not rendered anymore
+        if !open {
+            self.ui_toolbar_group = UiToolbarGroup::None;
+        }
+    }
+
+    fn render_bottom_panel(&mut self, ctx: &Context, _: &mut Frame) {
+        // define a TopBottomPanel widget
+        TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                match self.game.state() {
+                    GameState::Ready => {
+                        let ready_text = match self.game.start_spot() {
+                            Some(_) => "Ready. Start on the highlighted spot",
+                            None => "Ready",
+                        };
+                        ui.add(Label::new(
+                            RichText::new(ready_text)
+                                .small()
+                                .color(Self::READY_COLOR)
+                                .text_style(TextStyle::Monospace),
+                        ));
                     },
-                    SpotState::HiddenMine => {
-                        let _ = ui.add_enabled(false, Button::new(
-                            RichText::new(Self::MINE_CAHR)
-                            .color(Self::MINE_COLOR)
-                            .monospace()
-                            .size(size)
+                    GameState::Running => {
+                        let hint_text = match self.hint {
+                            // Flags were placed before the first step
+                            Hint::None if self.game.start_spot().is_some() && self.game.minefield().mines() == 0 => {
+                                Some("Start on the highlighted spot")
+                            },
+                            Hint::None => None,
+                            Hint::Spot(_, _, Deduction::Safe) => Some("Hint: the highlighted spot is safe"),
+                            Hint::Spot(_, _, Deduction::Mine) => Some("Hint: the highlighted spot is a mine"),
+                            Hint::Unavailable => Some("Hint: nothing can be deduced, you will have to guess"),
+                        };
+
+                        if let Some(hint_text) = hint_text {
+                            ui.add(Label::new(
+                                RichText::new(hint_text)
+                                    .small()
+                                    .color(Self::READY_COLOR)
+                                    .text_style(TextStyle::Monospace),
+                            ));
+                        }
+
+                        if self.game.may_need_guessing() {
+                            ui.add(Label::new(
+                                RichText::new("No minefield without guessing was found in time, this one may need guessing")
+                                    .small()
+                                    .color(Self::LOST_COLOR)
+                                    .text_style(TextStyle::Monospace),
+                            ));
+                        }
+                    },
+                    GameState::Stopped(is_won) => {
+                        if is_won {
+                            let won_text = match self.game.hints_used() {
+                                0 => "You WIN!".to_owned(),
+                                1 => "You WIN! (with 1 hint)".to_owned(),
+                                hints => format!("You WIN! (with {} hints)", hints),
+                            };
+                            let won_text = if self.game.is_practice() { format!("{} Practice game.", won_text) } else { won_text };
+
+                            ui.add(Label::new(
+                                RichText::new(won_text)
+                                    .color(Self::WON_COLOR)
+                                    .text_style(TextStyle::Monospace),
+                            ));
+                        } else {
+                            ui.add(Label::new(
+                                RichText::new("You lost.")
+                                    .color(Self::LOST_COLOR)
+                                    .text_style(TextStyle::Monospace),
+                            ));
+
+                            if self.game.can_undo() {
+                                ui.add(Label::new(
+                                    RichText::new("Practice game: undo the last move to keep playing")
+                                        .small()
+                                        .color(Self::READY_COLOR)
+                                        .text_style(TextStyle::Monospace),
+                                ));
+                            }
+                        }
+
+                        ui.add(Label::new(
+                            RichText::new(self.metrics_text())
+                                .small()
+                                .color(Self::READY_COLOR)
+                                .text_style(TextStyle::Monospace),
                         ));
                     },
-                    SpotState::FlaggedEmpty { neighboring_mines: _ } => {
-                        let _ = ui.add_enabled(false, Button::new(
-                            RichText::new(Self::FLAG_CHAR)
-                            .color(Self::FLAG_COLOR_WRONG)
+                }
+
+                let seed_text = match self.game.config().seed {
+                    Seed::Daily => format!("Daily challenge of {}", Seed::date_of(self.game.seed())),
+                    _ => format!("Seed {}", self.game.seed()),
+                };
+                let seed_label = ui.add(Label::new(
+                    RichText::new(seed_text)
+                        .small()
+                        .color(Self::READY_COLOR)
+                        .text_style(TextStyle::Monospace),
+                ));
+                if self.game.config().seed != Seed::Random {
+                    seed_label.on_hover_text("Everyone who plays this seed gets the same minefield");
+                }
+            })
+        });
+    }
+
+    fn render_minefield(&mut self, ctx: &Context, frame: &mut Frame) {
+        // Stop highlighting the hinted spot once the player has acted on the hint
+        if let Hint::Spot(x, y, deduction) = self.hint {
+            let is_acted_on = match (self.game.minefield().spot(x, y).unwrap().state, deduction) {
+                (SpotState::RevealedEmpty { neighboring_mines: _ }, _) => true,
+                (SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine, Deduction::Mine) => true,
+                (_, _) => false,
+            };
+
+            if is_acted_on {
+                self.hint = Hint::None;
+            }
+        }
+
+        // Chording with both buttons happens when either of them is released
+        let (primary_down, secondary_down) = {
+            let pointer = &ctx.input().pointer;
+            (pointer.button_down(PointerButton::Primary), pointer.button_down(PointerButton::Secondary))
+        };
+        let both_buttons_down = primary_down && secondary_down;
+        self.both_buttons_released = self.both_buttons_down && !both_buttons_down;
+        self.both_buttons_down = both_buttons_down;
+        self.is_both_buttons_press |= both_buttons_down && self.chording == Chording::BothButtons;
+
+        if self.show_probabilities && self.game.config().play_mode == PlayMode::Practice {
+            if self.probabilities_stale {
+                self.probabilities = probability::mine_probabilities(self.game.minefield());
+                self.probabilities_stale = false;
+            }
+        } else {
+            self.probabilities = None;
+            self.probabilities_stale = true;
+        }
+
+        if self.game.is_paused() && self.replay_viewer.is_none() {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| {
+                    if ui.add(Button::new(RichText::new("Paused").text_style(TextStyle::Heading)).frame(false))
+                        .on_hover_text("Click to continue")
+                        .clicked()
+                    {
+                        self.toggle_pause();
+                    }
+                });
+            });
+
+            return;
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+
+            let (width, height, topology) = match &self.replay_viewer {
+                Some(viewer) => (viewer.minefield.width(), viewer.minefield.height(), viewer.minefield.topology()),
+                None => (self.game.minefield().width(), self.game.minefield().height(), self.game.minefield().topology()),
+            };
+
+            let spacing = ui.spacing().item_spacing;
+            let available = ui.available_size();
+            let zoomed_size = Self::SPOT_SIZE * self.zoom as f32 / 100.0;
+
+            // The window grows or shrinks to fit the minefield, whenever the minefield or the zoom changes. The
+            // player may resize it afterwards.
+            let window_fit = Some((width, height, topology, self.zoom));
+            if self.window_fit != window_fit {
+                self.window_fit = window_fit;
+
+                let minefield_size = Self::minefield_size(width, height, topology, zoomed_size, spacing) + spacing;
+                let window_size = ctx.input().screen_rect.size() - available + minefield_size;
+                Self::resize_window(frame, Vec2::new(window_size.x.max(self.toolbar_width), window_size.y));
+            }
+
+            // Spots are as large as the zoom asks for, unless the minefield wouldn't fit in the window
+            let size = Self::fitting_spot_size(available, width, height, topology, spacing)
+                .min(zoomed_size)
+                .max(Self::MIN_SPOT_SIZE);
+
+            if topology == Topology::Hex {
+                self.render_hex_minefield(size, ui);
+                return;
+            }
+
+            TableBuilder::new(ui)
+                .cell_layout(Layout::centered_and_justified(Direction::LeftToRight))
+                .columns(Size::Absolute { initial: size - 1.0, range: (size - 1.0, size - 1.0) }, width as usize)
+                .body(|mut body| {
+                    for y in 0..height {
+                        body.row(size + 2.0, |mut row| {
+                            for x in 0..width {
+                                row.col(|ui| {
+                                    if let Some(viewer) = &self.replay_viewer {
+                                        Self::render_replay_spot(viewer, x, y, size, ui);
+                                    } else {
+                                        self.render_spot(x, y, size, ui);
+
+                                        if self.cursor == Some((x, y)) {
+                                            ui.painter().rect_stroke(
+                                                ui.min_rect(),
+                                                2.0,
+                                                Stroke::new(2.0, Self::CURSOR_COLOR)
+                                            );
+                                        }
+                                    }
+
+                                    if topology == Topology::Torus {
+                                        Self::render_wrapping_edges(x, y, width, height, ui);
+                                    }
+                                });
+                            }
+                        });
+                    }
+                }
+            );
+            
+        });
+
+        if !primary_down && !secondary_down {
+            self.is_both_buttons_press = false;
+        }
+    }
+
+    /// Size of the whole minefield on screen, with spots of the given size
+    fn minefield_size(width: u16, height: u16, topology: Topology, size: f32, spacing: Vec2) -> Vec2 {
+        match topology {
+            Topology::Hex => {
+                let radius = size / 3f32.sqrt();
+                Vec2::new(size * (width as f32 + 0.5), radius * (1.5 * (height as f32 - 1.0) + 2.0))
+            },
+            Topology::Square | Topology::Torus => Vec2::new(
+                width as f32 * (size - 1.0) + (width - 1) as f32 * spacing.x,
+                height as f32 * (size + 2.0) + (height - 1) as f32 * spacing.y,
+            ),
+        }
+    }
+
+    /// Largest size of the spots for which the whole minefield fits in the `available` space
+    fn fitting_spot_size(available: Vec2, width: u16, height: u16, topology: Topology, spacing: Vec2) -> f32 {
+        // The size of the minefield grows linearly with the size of its spots
+        let fixed = Self::minefield_size(width, height, topology, 0.0, spacing);
+        let per_point = Self::minefield_size(width, height, topology, 1.0, spacing) - fixed;
+
+        ((available.x - fixed.x) / per_point.x).min((available.y - fixed.y) / per_point.y)
+    }
+
+    /// Resize the native window. The web app can't resize the page it lives in, so its spots shrink to fit instead.
+    fn resize_window(frame: &mut Frame, size: Vec2) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // A fullscreen window already takes all the room there is
+            if !frame.info().window_info.fullscreen {
+                frame.set_window_size(size);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (frame, size);
+        }
+    }
+
+    /// Render one spot/tile at the given field coordinates
+    fn render_spot(&mut self, x: u16, y: u16, size: f32, ui: &mut Ui) {
+        let spot = *self.game.minefield().spot(x, y).unwrap();
+        let spot_fill = self.spot_fill(x, y);
+        let probability = self.spot_probability(x, y);
+
+        match self.game.state() {
+            GameState::Ready | GameState::Running => {
+                let spot_response = match spot.state {
+                    SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine => {
+                        let mut hidden_btn = Button::new(
+                            RichText::new(Self::HIDDEN_SPOT_CHAR)
+                            .color(Self::HIDDEN_SPOT_COLOR)
                             .monospace()
                             .size(size)
-                        ));
+                        );
+                        if let Some(spot_fill) = spot_fill {
+                            hidden_btn = hidden_btn.fill(spot_fill);
+                        }
+                        let mut hidden_btn = ui.add_enabled(true, hidden_btn);
+                        if let Some(probability) = probability {
+                            hidden_btn = hidden_btn.on_hover_text(format!("{:.1}% mine", probability * 100.0));
+                        }
+
+                        hidden_btn
                     },
-                    SpotState::FlaggedMine => {
-                        let _ = ui.add_enabled(false, Button::new(
-                            RichText::new(Self::FLAG_CHAR)
+                    SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine => {
+                        let mut flag_btn = Button::new(
+                            RichText::new(SpotLook::counted(Self::FLAG_CHAR, spot.flags))
                             .color(Self::FLAG_COLOR_CORRECT)
                             .monospace()
                             .size(size)
-                        ));
+                        );
+                        if let Some(spot_fill) = spot_fill {
+                            flag_btn = flag_btn.fill(spot_fill);
+                        }
+                        let mut flag_btn = ui.add_enabled(true, flag_btn);
+                        if let Some(probability) = probability {
+                            flag_btn = flag_btn.on_hover_text(format!("{:.1}% mine", probability * 100.0));
+                        }
+
+                        flag_btn
                     },
+
                     SpotState::RevealedEmpty { neighboring_mines } => {
-                        let _ = ui.add_enabled(is_won, Label::new(
-                            RichText::new(Self::EMPTY_SPOT_CHARS[neighboring_mines as usize])
-                            .color(Self::EMPTY_SPOT_COLORS[neighboring_mines as usize])
+                        let (text, color) = Self::empty_spot_look(neighboring_mines);
+                        let empty_lbl = Label::new(
+                            RichText::new(text)
+                            .color(color)
                             .monospace()
                             .size(size)
-                        ));
+                        );
+
+                        ui.add_enabled(true, empty_lbl.sense(Sense::click()))
                     },
                     SpotState::ExplodedMine => {
-                        let _ = ui.add_enabled(false, Button::new(
-                            RichText::new(Self::MINE_EXPLODED_CHAR)
-                            .color(Self::MINE_EPLODED_COLOR)
-                            .monospace()
-                            .size(size)
-                        ));
+                        unreachable!()
                     },
+                };
+
+                self.click_spot(x, y, SpotClicks::of(&spot_response));
+            },
+
+            GameState::Stopped(is_won) => {
+                Self::render_finished_spot(spot, is_won, size, ui);
+            },
+        }
+    }
+
+    /// Mark the sides of an edge spot on a torus, which continue on the opposite edge of the minefield
+    fn render_wrapping_edges(x: u16, y: u16, width: u16, height: u16, ui: &mut Ui) {
+        let rect = ui.max_rect();
+        let stroke = Stroke::new(2.0, Self::WRAPPING_EDGE_COLOR);
+
+        if x == 0 {
+            ui.painter().line_segment([rect.left_top(), rect.left_bottom()], stroke);
+        }
+        if x == width - 1 {
+            ui.painter().line_segment([rect.right_top(), rect.right_bottom()], stroke);
+        }
+        if y == 0 {
+            ui.painter().line_segment([rect.left_top(), rect.right_top()], stroke);
+        }
+        if y == height - 1 {
+            ui.painter().line_segment([rect.left_bottom(), rect.right_bottom()], stroke);
+        }
+    }
+
+    /// Highlight of a spot: the start of a seeded game, the hint, or else the probability of the spot being a mine
+    fn spot_fill(&self, x: u16, y: u16) -> Option<Color32> {
+        match self.hint {
+            _ if self.game.start_spot() == Some((x, y)) && self.game.minefield().mines() == 0 => Some(Self::START_SPOT_COLOR),
+            Hint::Spot(hx, hy, Deduction::Safe) if (hx, hy) == (x, y) => Some(Self::HINT_SAFE_COLOR),
+            Hint::Spot(hx, hy, Deduction::Mine) if (hx, hy) == (x, y) => Some(Self::HINT_MINE_COLOR),
+            _ => self.spot_probability(x, y).map(Self::probability_color),
+        }
+    }
+
+    fn spot_probability(&self, x: u16, y: u16) -> Option<f64> {
+        self.probabilities.as_ref().and_then(|probabilities| probabilities.get(&(x, y)).copied())
+    }
+
+    /// Act on the clicks on a spot of the running game, however the minefield is rendered
+    fn click_spot(&mut self, x: u16, y: u16, clicks: SpotClicks) {
+        match self.game.minefield().spot(x, y).unwrap().state {
+            SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine => {
+                if clicks.primary && !self.is_both_buttons_press {
+                    self.game.reveal(x, y);
+                }
+
+                if clicks.secondary && !self.is_both_buttons_press {
+                    self.game.flag(x, y);
+                }
+            },
+            SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine => {
+                if clicks.secondary && !self.is_both_buttons_press {
+                    self.game.flag(x, y);
+                }
+            },
+            SpotState::RevealedEmpty { neighboring_mines: _ } => {
+                let is_chord = match self.chording {
+                    Chording::MiddleButton => false,
+                    Chording::BothButtons => self.both_buttons_released && clicks.hovered,
+                    Chording::PrimaryButton => clicks.primary,
+                };
+
+                if is_chord || clicks.middle {
+                    self.game.chord(x, y);
+                } else if (clicks.primary || clicks.secondary) && !self.is_both_buttons_press {
+                    // Clicking a revealed spot without chording is a wasted click
+                    self.game.waste_click();
+                }
+            },
+            SpotState::ExplodedMine => {},
+        }
+    }
+
+    /// Render the whole minefield as a grid of hexagons. Hexagons can't be laid out as widgets, so they are painted,
+    /// and the spot under the pointer is the one whose center is closest to it.
+    fn render_hex_minefield(&mut self, size: f32, ui: &mut Ui) {
+        let minefield = match &self.replay_viewer {
+            Some(viewer) => &viewer.minefield,
+            None => self.game.minefield(),
+        };
+        let (width, height) = (minefield.width(), minefield.height());
+
+        // `None` while the game is being played, otherwise whether it was won
+        let finished = match (&self.replay_viewer, self.game.state()) {
+            (Some(viewer), _) => viewer.is_finished().then_some(viewer.replay.is_won),
+            (None, GameState::Stopped(is_won)) => Some(is_won),
+            (None, _) => None,
+        };
+        let is_playing = self.replay_viewer.is_none() && finished.is_none();
+
+        // Pointy-top hexagons, `size` wide, with odd rows shifted right by half a hexagon
+        let radius = size / 3f32.sqrt();
+        let (rect, response) = ui.allocate_exact_size(
+            Vec2::new(size * (width as f32 + 0.5), radius * (1.5 * (height as f32 - 1.0) + 2.0)),
+            Sense::click()
+        );
+        let center = |(x, y): (u16, u16)| {
+            rect.min + Vec2::new(size * (x as f32 + 0.5 + 0.5 * (y % 2) as f32), radius * (1.0 + 1.5 * y as f32))
+        };
+
+        let hovered = response.hover_pos().and_then(|pos| {
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .min_by(|a, b| (center(*a) - pos).length_sq().total_cmp(&(center(*b) - pos).length_sq()))
+                .filter(|coords| (center(*coords) - pos).length() <= radius)
+        });
+
+        let painter = ui.painter();
+        for y in 0..height {
+            for x in 0..width {
+                let spot = *minefield.spot(x, y).unwrap();
+                let (mut fill, text, text_color) = Self::hex_spot_look(spot, finished.is_some(), ui.visuals());
+
+                let is_unrevealed = matches!(
+                    spot.state,
+                    SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine |
+                    SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine
+                );
+
+                if is_playing && is_unrevealed {
+                    if let Some(spot_fill) = self.spot_fill(x, y) {
+                        fill = spot_fill;
+                    } else if hovered == Some((x, y)) {
+                        fill = ui.visuals().widgets.hovered.bg_fill;
+                    }
                 }
+
+                let stroke = if is_playing && self.cursor == Some((x, y)) {
+                    Stroke::new(2.0, Self::CURSOR_COLOR)
+                } else {
+                    Stroke::none()
+                };
+
+                let points = (0..6)
+                    .map(|corner| {
+                        let angle = std::f32::consts::FRAC_PI_3 * corner as f32 + std::f32::consts::FRAC_PI_6;
+                        center((x, y)) + Vec2::angled(angle) * (radius - 1.0)
+                    })
+                    .collect();
+
+                painter.add(Shape::convex_polygon(points, fill, stroke));
+                painter.text(center((x, y)), Align2::CENTER_CENTER, text, FontId::monospace(size * 0.6), text_color);
+            }
+        }
+
+        if let (Some((x, y)), true) = (hovered, is_playing) {
+            let mut response = response;
+            if let Some(probability) = self.spot_probability(x, y) {
+                response = response.on_hover_text(format!("{:.1}% mine", probability * 100.0));
+            }
+
+            self.click_spot(x, y, SpotClicks::of(&response));
+        }
+    }
+
+    /// Fill, symbol and symbol color of a spot on the hex grid. All mines are revealed once the game is finished.
+    fn hex_spot_look(spot: Spot, is_finished: bool, visuals: &Visuals) -> (Color32, String, Color32) {
+        let hidden_fill = visuals.widgets.inactive.bg_fill;
+
+        match spot.look(is_finished) {
+            SpotLook::Hidden => (hidden_fill, Self::HIDDEN_SPOT_CHAR.to_owned(), Self::HIDDEN_SPOT_COLOR),
+            SpotLook::Revealed(neighboring_mines) => {
+                let (text, color) = Self::empty_spot_look(neighboring_mines);
+                (visuals.faint_bg_color, text, color)
+            },
+            SpotLook::Flags(flags) | SpotLook::CheckedFlags { flags, is_right: true } => {
+                (hidden_fill, SpotLook::counted(Self::FLAG_CHAR, flags), Self::FLAG_COLOR_CORRECT)
+            },
+            SpotLook::CheckedFlags { flags, is_right: false } => {
+                (hidden_fill, SpotLook::counted(Self::FLAG_CHAR, flags), Self::FLAG_COLOR_WRONG)
+            },
+            SpotLook::Mines(mines) => (hidden_fill, SpotLook::counted(Self::MINE_CAHR, mines), Self::MINE_COLOR),
+            SpotLook::Exploded(mines) => {
+                (hidden_fill, SpotLook::counted(Self::MINE_EXPLODED_CHAR, mines), Self::MINE_EPLODED_COLOR)
             },
         }
     }
 
-    fn game_over(&mut self, is_won: bool) {
-        self.game_state = GameState::Stopped(is_won);
-        self.timer.stop();
+    /// Symbol and color of a revealed empty spot with the given number of neighboring mines
+    fn empty_spot_look(neighboring_mines: u8) -> (String, Color32) {
+        let text = if neighboring_mines > 0 { neighboring_mines.to_string() } else { " ".to_owned() };
+        let color = Self::EMPTY_SPOT_COLORS[(neighboring_mines as usize).min(Self::EMPTY_SPOT_COLORS.len() - 1)];
+        (text, color)
     }
 
-    fn check_ready_to_running(&mut self) {
-        if self.game_state == GameState::Ready {
-            self.game_state = GameState::Running;
-            self.timer.start();
+    /// Render one spot/tile of a finished game, revealing all mines
+    fn render_finished_spot(spot: Spot, is_won: bool, size: f32, ui: &mut Ui) {
+        match spot.state {
+            SpotState::HiddenEmpty { neighboring_mines: _ } => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(Self::HIDDEN_SPOT_CHAR)
+                    .color(Self::HIDDEN_SPOT_COLOR)
+                    .monospace()
+                    .size(size)
+                ));                        
+            },
+            SpotState::HiddenMine => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::MINE_CAHR, spot.mines))
+                    .color(Self::MINE_COLOR)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::FlaggedEmpty { neighboring_mines: _ } => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::FLAG_CHAR, spot.flags))
+                    .color(Self::FLAG_COLOR_WRONG)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::FlaggedMine => {
+                let flag_color = if spot.flags == spot.mines { Self::FLAG_COLOR_CORRECT } else { Self::FLAG_COLOR_WRONG };
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::FLAG_CHAR, spot.flags))
+                    .color(flag_color)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::RevealedEmpty { neighboring_mines } => {
+                let (text, color) = Self::empty_spot_look(neighboring_mines);
+                let _ = ui.add_enabled(is_won, Label::new(
+                    RichText::new(text)
+                    .color(color)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::ExplodedMine => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::MINE_EXPLODED_CHAR, spot.mines))
+                    .color(Self::MINE_EPLODED_COLOR)
+                    .monospace()
+                    .size(size)
+                ));
+            },
         }
     }
 
+    /// Render one spot/tile of the game being played back, which can't be interacted with
+    fn render_replay_spot(viewer: &ReplayViewer, x: u16, y: u16, size: f32, ui: &mut Ui) {
+        let spot = *viewer.minefield.spot(x, y).unwrap();
+
+        if viewer.is_finished() {
+            Self::render_finished_spot(spot, viewer.replay.is_won, size, ui);
+            return;
+        }
+
+        match spot.state {
+            SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(Self::HIDDEN_SPOT_CHAR)
+                    .color(Self::HIDDEN_SPOT_COLOR)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::FLAG_CHAR, spot.flags))
+                    .color(Self::FLAG_COLOR_CORRECT)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::RevealedEmpty { neighboring_mines } => {
+                let (text, color) = Self::empty_spot_look(neighboring_mines);
+                let _ = ui.add(Label::new(
+                    RichText::new(text)
+                    .color(color)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+            SpotState::ExplodedMine => {
+                let _ = ui.add_enabled(false, Button::new(
+                    RichText::new(SpotLook::counted(Self::MINE_EXPLODED_CHAR, spot.mines))
+                    .color(Self::MINE_EPLODED_COLOR)
+                    .monospace()
+                    .size(size)
+                ));
+            },
+        }
+    }
+
+    /// Keyboard shortcuts which apply to the whole app, and keyboard play
+    fn handle_shortcuts(&mut self, ctx: &Context) {
+        if let Some((action, slot)) = self.key_binding_capture {
+            let pressed_key = ctx.input().events.iter().find_map(|event| match event {
+                egui::Event::Key { key, pressed: true, modifiers } => Some((*key, *modifiers)),
+                _ => None,
+            });
+
+            if let Some((key, modifiers)) = pressed_key {
+                ctx.input_mut().consume_key(modifiers, key);
+
+                // Escape only cancels the binding
+                if key != Key::Escape {
+                    self.key_bindings.bind(action, slot, Some(key));
+                }
+                self.key_binding_capture = None;
+            }
+
+            return;
+        }
+
+        // Text fields have their own undo, and use the keys for typing
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (undo, redo) = {
+            let input = ctx.input();
+            let command = input.modifiers.command;
+            let shift = input.modifiers.shift;
+            (
+                command && !shift && input.key_pressed(Key::Z),
+                command && (input.key_pressed(Key::Y) || (shift && input.key_pressed(Key::Z))),
+            )
+        };
+
+        if undo {
+            self.game.undo();
+        } else if redo {
+            self.game.redo();
+        }
+
+        // Consume the keys, so that they don't also click whichever button has the focus
+        let pressed: Vec<KeyAction> = KeyAction::ALL
+            .into_iter()
+            .filter(|action| {
+                let mut input = ctx.input_mut();
+                self.key_bindings.keys(*action).iter().flatten().any(|key| input.consume_key(Modifiers::NONE, *key))
+            })
+            .collect();
+
+        for action in pressed {
+            self.handle_key_action(action);
+        }
+    }
+
+    fn handle_key_action(&mut self, action: KeyAction) {
+        let (width, height) = (self.game.minefield().width(), self.game.minefield().height());
+
+        // The cursor shows up in the middle of the minefield, the first time it is moved
+        let (x, y) = self.cursor.unwrap_or((width / 2, height / 2));
+        let is_playing = matches!(self.game.state(), GameState::Ready | GameState::Running) && !self.game.is_paused();
+
+        match action {
+            KeyAction::Up => self.cursor = Some(self.game.minefield().next_coords(x, y, Heading::Up)),
+            KeyAction::Down => self.cursor = Some(self.game.minefield().next_coords(x, y, Heading::Down)),
+            KeyAction::Left => self.cursor = Some(self.game.minefield().next_coords(x, y, Heading::Left)),
+            KeyAction::Right => self.cursor = Some(self.game.minefield().next_coords(x, y, Heading::Right)),
+            KeyAction::Reveal | KeyAction::Flag | KeyAction::Chord if is_playing && self.cursor.is_some() => {
+                match (action, self.game.minefield().spot(x, y).unwrap().state) {
+                    (KeyAction::Reveal, SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine) => {
+                        self.game.reveal(x, y);
+                    },
+                    (
+                        KeyAction::Flag,
+                        SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine |
+                        SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine
+                    ) => {
+                        self.game.flag(x, y);
+                    },
+                    (KeyAction::Chord, SpotState::RevealedEmpty { neighboring_mines: _ }) => {
+                        self.game.chord(x, y);
+                    },
+                    (_, _) => {},
+                }
+            },
+            KeyAction::Reveal | KeyAction::Flag | KeyAction::Chord => {},
+            KeyAction::Restart => {
+                let cursor = self.cursor;
+                self.refresh();
+                self.cursor = cursor;
+            },
+            KeyAction::Settings => self.toggle_settings(),
+            KeyAction::Pause => self.toggle_pause(),
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        if self.game.is_paused() {
+            self.game.unpause();
+        } else {
+            self.game.pause();
+        }
+    }
+
+    /// Is the app window focused (and, on the web, is its tab visible)?
+    fn has_focus(ctx: &Context) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ctx.input().raw.has_focus
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            // The web integration doesn't track focus
+            let _ = ctx;
+            web_sys::window()
+                .and_then(|window| window.document())
+                .map(|document| !document.hidden() && document.has_focus().unwrap_or(true))
+                .unwrap_or(true)
+        }
+    }
+
+    /// Open the settings window with the current config, or close it if it's open
+    fn toggle_settings(&mut self) {
+        if let UiToolbarGroup::Settings(_, _) = self.ui_toolbar_group {
+            self.ui_toolbar_group = UiToolbarGroup::None;
+        } else {
+            self.ui_toolbar_group = UiToolbarGroup::Settings(
+                self.game_config,
+                GameDifficulty::from_config(&self.game_config)
+            );
+        }
+    }
+
+    /// Read a replay from text, or (natively) from the file at the given path
+    fn load_replay(text: &str) -> Result<Replay, String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = std::path::Path::new(text.trim());
+            if path.is_file() {
+                let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                return Replay::from_text(&text);
+            }
+        }
+
+        Replay::from_text(text)
+    }
+
+    /// Continue a game which was saved in storage
+    fn resume(&mut self, saved_game: SavedGame) {
+        match Game::resume(self.game_config, saved_game) {
+            Some(game) => self.game = game,
+            None => tracing::warn!("Saved game does not match config {:?}. Starting a new game", self.game_config),
+        }
+    }
+
+    /// React to what happened in the game, since the last frame
+    fn handle_game_events(&mut self) {
+        for event in self.game.take_events() {
+            match event {
+                GameEvent::Revealed { .. } | GameEvent::Chorded { .. } | GameEvent::FlagToggled { .. } |
+                GameEvent::Undone | GameEvent::Redone => {
+                    self.hint = Hint::None;
+                    self.probabilities_stale = true;
+                },
+                GameEvent::Finished { .. } => self.game_over(),
+                // The lost game's replay is gone too, since the game is not finished after all
+                GameEvent::Revived => self.records.remove_last_replay(),
+                GameEvent::Started | GameEvent::Paused | GameEvent::Unpaused => {},
+            }
+        }
+    }
+
+    fn game_over(&mut self) {
+        if let Some(rank) = self.records.add(&FinishedGame::of(&self.game, &self.player_name)) {
+            tracing::debug!("New high score, rank {} for {:?}", rank + 1, self.game.config());
+
+            // Show off the new entry
+            self.ui_toolbar_group = UiToolbarGroup::HighScores(*self.game.config());
+        }
+    }
+
+    /// Time on the stopwatch, in seconds. A finished game shows the exact time, to the millisecond.
+    fn time_text(&self) -> String {
+        let seconds = self.game.millis() as f64 / 1000.0;
+
+        match self.game.state() {
+            GameState::Ready | GameState::Running => format!("{:.1}", seconds),
+            GameState::Stopped(_) => format!("{:.3}", seconds),
+        }
+    }
+
+    /// How well the finished game was played: the 3BV which was cleared, 3BV per second, efficiency (3BV cleared per
+    /// click), clicks per second, and how many clicks were wasted
+    fn metrics_text(&self) -> String {
+        let seconds = (self.game.millis() as f64 / 1000.0).max(0.001);
+        let solved_bbbv = self.game.minefield().solved_bbbv();
+        let efficiency = if self.game.clicks() > 0 { solved_bbbv as f64 * 100.0 / self.game.clicks() as f64 } else { 0.0 };
+
+        format!(
+            "3BV: {}/{}  3BV/s: {:.2}  Efficiency: {:.0}%  Clicks: {} ({} wasted)  Clicks/s: {:.2}",
+            solved_bbbv, self.game.bbbv(),
+            solved_bbbv as f64 / seconds,
+            efficiency,
+            self.game.clicks(), self.game.clicks().saturating_sub(self.game.effective_clicks()),
+            self.game.clicks() as f64 / seconds,
+        )
+    }
+
+    /// Ask the solver for one spot which can be deduced from the visible state of the minefield
+    fn show_hint(&mut self) {
+        self.hint = match self.game.hint() {
+            Some(((x, y), deduction)) => Hint::Spot(x, y, deduction),
+            None => Hint::Unavailable,
+        };
+    }
+
+    /// Heat-map color for the probability of a spot being a mine
+    fn probability_color(probability: f64) -> Color32 {
+        let probability = probability.clamp(0.0, 1.0) as f32;
+        Color32::from_rgb((160.0 * probability) as u8, (160.0 * (1.0 - probability)) as u8, 0)
+    }
+
+    /// Human readable name of a game configuration
+    pub fn config_name(config: &GameConfig) -> String {
+        let name = match GameDifficulty::from_config(config) {
+            GameDifficulty::Custom => format!("Custom {}x{}, {} mines", config.width, config.height, config.mines),
+            difficulty => format!("{:?}", difficulty),
+        };
+
+        let name = match config.first_click {
+            FirstClick::Safe => name,
+            FirstClick::Opening => format!("{} (opening)", name),
+        };
+
+        let name = match config.generator {
+            Generator::Random => name,
+            Generator::NoGuess => format!("{} (no guess)", name),
+        };
+
+        let name = match config.topology {
+            Topology::Square => name,
+            Topology::Hex => format!("{} (hex)", name),
+            Topology::Torus => format!("{} (torus)", name),
+        };
+
+        let name = match config.mines_per_spot {
+            1 => name,
+            mines_per_spot => format!("{} ({} mines per spot)", name, mines_per_spot),
+        };
+
+        match config.seed {
+            Seed::Random => name,
+            Seed::Fixed(seed) => format!("{} (seed {})", name, seed),
+            Seed::Daily => format!("{} (daily)", name),
+        }
+    }
+
+    /// Start a new game on the given board. Anyone can decode a board and see where its mines are, so it is only
+    /// played for practice.
+    /// Play a game on a board which was passed on. The board's config is only the game's: the next game is played with
+    /// the player's own config again.
+    fn load_board(&mut self, mut board: Board) {
+        tracing::debug!("Loading board {:?}", board.config);
+
+        board.config.play_mode = PlayMode::Practice;
+        self.refresh();
+        self.game = Game::with_board(board);
+    }
+
+    /// Link to the page the web app runs in, which opens the board of the given code
+    #[cfg(target_arch = "wasm32")]
+    fn board_link(board_code: &str) -> Option<String> {
+        let location = web_sys::window()?.location();
+        Some(format!("{}{}?board={}", location.origin().ok()?, location.pathname().ok()?, board_code))
+    }
+
     fn refresh(&mut self) {
-        let minefield = Minefield::new(self.game_config.width, self.game_config.height).with_mines(self.game_config.mines);
         let game_config = self.game_config;
+        let records = std::mem::take(&mut self.records);
+        let player_name = std::mem::take(&mut self.player_name);
+        let show_probabilities = self.show_probabilities;
+        let key_bindings = self.key_bindings;
+        let chording = self.chording;
+        let zoom = self.zoom;
+        let toolbar_width = self.toolbar_width;
+        let window_fit = self.window_fit;
         *self = Self {
-            minefield,
+            game: Game::new(game_config),
             game_config,
+            records,
+            player_name,
+            show_probabilities,
+            key_bindings,
+            chording,
+            zoom,
+            toolbar_width,
+            window_fit,
             ..Default::default()
         };
     }
@@ -502,165 +2033,208 @@ impl Default for MinesweepRsApp {
     fn default() -> Self {
         let game_config = GameConfig::default();
         Self {
-            minefield: Minefield::new(game_config.width, game_config.height).with_mines(game_config.mines),
-            placed_flags: 0,
-            seconds_lapsed: 0,
-            timer: AppTimer::default(),
-            game_state: GameState::default(),
+            game: Game::new(game_config),
             game_config,
             ui_toolbar_group: UiToolbarGroup::default(),
+            records: Records::default(),
+            player_name: Self::DEFAULT_PLAYER_NAME.to_owned(),
+            hint: Hint::default(),
+            show_probabilities: false,
+            probabilities: None,
+            probabilities_stale: true,
+            replay_viewer: None,
+            replay_text: String::new(),
+            replay_message: None,
+            board_text: String::new(),
+            board_message: None,
+            key_bindings: KeyBindings::default(),
+            key_binding_capture: None,
+            cursor: None,
+            chording: Chording::default(),
+            zoom: Self::DEFAULT_ZOOM,
+            toolbar_width: 0.0,
+            window_fit: None,
+            both_buttons_down: false,
+            both_buttons_released: false,
+            is_both_buttons_press: false,
         }
     }
 }
 
-/// Current state of the game
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum GameState {
-    /// Game is ready to start running
-    Ready,
+/// Help given to the player by the solver
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+enum Hint {
+    /// No hint was asked for
+    #[default]
+    None,
 
-    /// Game is running
-    Running,
+    /// What is in the spot at the given coordinates can be deduced
+    Spot(u16, u16, Deduction),
 
-    /// Game is stopped, and was either won (`true`), or lost (`false`)
-    Stopped(bool)
+    /// Nothing can be deduced from what is visible, so the player has to guess
+    Unavailable,
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::Ready
+/// Clicks on a spot of the minefield, in the current frame
+struct SpotClicks {
+    primary: bool,
+    secondary: bool,
+    middle: bool,
+    /// Is the pointer over the spot?
+    hovered: bool,
+}
+
+impl SpotClicks {
+    fn of(response: &Response) -> Self {
+        Self {
+            primary: response.clicked_by(PointerButton::Primary),
+            secondary: response.clicked_by(PointerButton::Secondary),
+            middle: response.clicked_by(PointerButton::Middle),
+            hovered: response.hovered(),
+        }
     }
 }
 
+#[derive(Default)]
 enum UiToolbarGroup {
+    #[default]
     None,
     About,
-    Settings(GameConfig),
+    Settings(GameConfig, GameDifficulty),
+    HighScores(GameConfig),
+    Statistics(GameConfig),
+    Replays,
+    Share,
 }
 
-impl Default for UiToolbarGroup {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct GameConfig {
-    pub width: u16,
-    pub height: u16,
-    pub mines: u32,
+/// Something the player can do with the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Reveal,
+    Flag,
+    Chord,
+    Restart,
+    Settings,
+    Pause,
 }
 
-impl Default for GameConfig {
-    fn default() -> Self {
-        Self { width: 10, height: 10, mines: 10 }
-    }
+impl KeyAction {
+    pub const ALL: [KeyAction; 10] = [
+        KeyAction::Up, KeyAction::Down, KeyAction::Left, KeyAction::Right,
+        KeyAction::Reveal, KeyAction::Flag, KeyAction::Chord,
+        KeyAction::Restart, KeyAction::Settings, KeyAction::Pause,
+    ];
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum GameDifficulty {
-    Easy,
-    Medium,
-    Hard,
+/// Keys bound to each `KeyAction`. Every action can have up to two keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub up: [Option<Key>; 2],
+    pub down: [Option<Key>; 2],
+    pub left: [Option<Key>; 2],
+    pub right: [Option<Key>; 2],
+    pub reveal: [Option<Key>; 2],
+    pub flag: [Option<Key>; 2],
+    pub chord: [Option<Key>; 2],
+    pub restart: [Option<Key>; 2],
+    pub settings: [Option<Key>; 2],
+    pub pause: [Option<Key>; 2],
 }
 
-impl GameDifficulty {
-    pub const EASY: GameConfig = GameConfig { width: 10, height: 10, mines: 10 };
-    pub const MEDIUM: GameConfig = GameConfig { width: 16, height: 16, mines: 40 };
-    pub const HARD: GameConfig = GameConfig { width: 30, height: 16, mines: 99 };
-
-    pub fn from_config(config: &GameConfig) -> Self {
-        if *config == Self::EASY {
-            Self::Easy
-        } else if *config == Self::MEDIUM {
-            Self::Medium
-        } else if *config == Self::HARD {
-            Self::Hard
-        } else {
-            unreachable!()
+impl KeyBindings {
+    pub fn keys(&self, action: KeyAction) -> &[Option<Key>; 2] {
+        match action {
+            KeyAction::Up => &self.up,
+            KeyAction::Down => &self.down,
+            KeyAction::Left => &self.left,
+            KeyAction::Right => &self.right,
+            KeyAction::Reveal => &self.reveal,
+            KeyAction::Flag => &self.flag,
+            KeyAction::Chord => &self.chord,
+            KeyAction::Restart => &self.restart,
+            KeyAction::Settings => &self.settings,
+            KeyAction::Pause => &self.pause,
         }
     }
-}
-
-/// Native app timer
-#[cfg(not(target_arch = "wasm32"))]
-#[derive(Default)]
-struct AppTimer {
-    timer: Option<Timer>,
-    guard: Option<Guard>,
-    rx: Option<Receiver<()>>
-}
 
-/// WASM app timer
-#[cfg(target_arch = "wasm32")]
-#[derive(Default)]
-struct AppTimer {
-    timer: Option<Interval>,
-    rx: Option<Receiver<()>>
-}
-
-impl AppTimer {
-    pub fn stop(&mut self) {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.guard = None;
-            self.timer = None;
-            self.rx = None;
-        }
-
-        #[cfg(target_arch = "wasm32")]
-        {
-            if let Some(prev_interval) = self.timer.take() {
-                prev_interval.cancel();
-            }            
+    fn keys_mut(&mut self, action: KeyAction) -> &mut [Option<Key>; 2] {
+        match action {
+            KeyAction::Up => &mut self.up,
+            KeyAction::Down => &mut self.down,
+            KeyAction::Left => &mut self.left,
+            KeyAction::Right => &mut self.right,
+            KeyAction::Reveal => &mut self.reveal,
+            KeyAction::Flag => &mut self.flag,
+            KeyAction::Chord => &mut self.chord,
+            KeyAction::Restart => &mut self.restart,
+            KeyAction::Settings => &mut self.settings,
+            KeyAction::Pause => &mut self.pause,
         }
     }
 
-    pub fn start(&mut self) {
-        let (tx, rx) = channel();
-
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            use chrono::Duration;
-
-            let timer = Timer::new();
-            let guard = timer.schedule_repeating(Duration::seconds(1), move || {
-                    tx.send(()).unwrap();
-            });
-    
-            self.timer = Some(timer);
-            self.guard = Some(guard);
-            self.rx = Some(rx);
+    /// Bind a key (or no key) to one of the two slots of an action. A key is never bound to more than one action, so
+    /// it is taken away from whatever action it was bound to before.
+    pub fn bind(&mut self, action: KeyAction, slot: usize, key: Option<Key>) {
+        if key.is_some() {
+            for other in KeyAction::ALL {
+                for other_key in self.keys_mut(other) {
+                    if *other_key == key {
+                        *other_key = None;
+                    }
+                }
+            }
         }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            let interval = Interval::new(1000, move || {
-                tx.send(()).unwrap();
-            }); 
-            
-            self.timer = Some(interval);
-            self.rx = Some(rx);
-        }        
+        self.keys_mut(action)[slot] = key;
     }
+}
 
-    pub fn poll(&self) -> Option<()> {
-        if let Some(rx) = &self.rx {
-            rx.try_iter().next()
-        } else {
-            None
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: [Some(Key::ArrowUp), Some(Key::K)],
+            down: [Some(Key::ArrowDown), Some(Key::J)],
+            left: [Some(Key::ArrowLeft), Some(Key::H)],
+            right: [Some(Key::ArrowRight), Some(Key::L)],
+            reveal: [Some(Key::Space), None],
+            flag: [Some(Key::F), None],
+            chord: [Some(Key::D), None],
+            restart: [Some(Key::R), None],
+            settings: [Some(Key::S), None],
+            pause: [Some(Key::P), None],
         }
     }
 }
 
+/// How the player steps on all the neighbors of a revealed spot at once (chording). Clicking the spot with the middle
+/// button always works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Chording {
+    /// Only with the middle button
+    #[default]
+    MiddleButton,
+
+    /// Press both the primary and secondary buttons on the spot, and release either of them
+    BothButtons,
+
+    /// Click the spot with the primary button
+    PrimaryButton,
+}
+
+
 
 #[cfg(target_arch = "wasm32")]
 use eframe::wasm_bindgen::{self, prelude::*};
 
-/// WASM entry point
+/// WASM entry point. The `query` string of the page URL may hold the code of a board to start with, as in
+/// `?board=<code>`.
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
-pub fn main_web(canvas_id: &str) {
+pub fn main_web(canvas_id: &str, query: &str) {
     use eframe::WebOptions;
 
     tracing_wasm::set_as_global_default();
@@ -671,6 +2245,95 @@ pub fn main_web(canvas_id: &str) {
         ..Default::default()
     };
 
-    eframe::start_web(canvas_id, options, Box::new(|cc| Box::new(MinesweepRsApp::default().with_context(cc))))
-        .expect("Failed to launch egui-minesweep-rs");
-}
\ No newline at end of file
+    let board_code = query
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|param| param.strip_prefix("board="))
+        .map(str::to_owned);
+
+    eframe::start_web(canvas_id, options, Box::new(move |cc| {
+        let app = MinesweepRsApp::default().with_context(cc);
+        Box::new(match board_code {
+            Some(board_code) => app.with_board_code(&board_code),
+            None => app,
+        })
+    })).expect("Failed to launch egui-minesweep-rs");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minefield::StepResult;
+
+    #[test]
+    fn refresh_starts_a_new_game() {
+        let mut app = MinesweepRsApp::default().with_configs(GameDifficulty::MEDIUM);
+        app.player_name = "Tester".to_owned();
+
+        app.game.flag(0, 0);
+        app.game.reveal(5, 5);
+        app.show_hint();
+        app.handle_game_events();
+        assert_eq!(app.game.state(), GameState::Running);
+        assert_eq!(app.game.minefield().mines(), GameDifficulty::MEDIUM.mines);
+
+        app.refresh();
+
+        assert_eq!(app.game.state(), GameState::Ready);
+        assert_eq!(app.game.minefield().mines(), 0);
+        assert_eq!(app.game.placed_flags(), 0);
+        assert_eq!(app.game.clicks(), 0);
+        assert_eq!(app.game.hints_used(), 0);
+        assert!(app.game.replay().actions.is_empty());
+        assert!(!app.game.can_undo());
+        assert_eq!(app.hint, Hint::None);
+
+        // The config and the player's data are kept
+        assert_eq!(app.game_config, GameDifficulty::MEDIUM);
+        assert_eq!(*app.game.config(), GameDifficulty::MEDIUM);
+        assert_eq!(app.player_name, "Tester");
+    }
+
+    #[test]
+    fn finished_game_is_recorded() {
+        let mut app = MinesweepRsApp::default().with_configs(GameDifficulty::EASY);
+
+        // Step on spots until one of them is a mine
+        let coords: Vec<(u16, u16)> = app.game.minefield().spots().map(|(coords, _)| *coords).collect();
+        for (x, y) in coords {
+            if app.game.reveal(x, y) == StepResult::Boom {
+                break;
+            }
+        }
+        app.handle_game_events();
+
+        assert_eq!(app.game.state(), GameState::Stopped(false));
+        assert_eq!(app.records.replays.len(), 1);
+        assert_eq!(app.records.replays[0].player, MinesweepRsApp::DEFAULT_PLAYER_NAME);
+        assert_eq!(app.records.statistics.table(&GameDifficulty::EASY).map(|table| table.played), Some(1));
+    }
+
+    #[test]
+    fn loaded_board_is_not_ranked() {
+        let config = GameConfig { width: 5, height: 5, mines: 1, ..GameDifficulty::EASY };
+        let code = Board { config, mines: vec![(0, 0)], start: None }.to_code();
+        let mut app = MinesweepRsApp::default().with_board_code(&code);
+
+        assert_eq!(app.game.config().play_mode, PlayMode::Practice);
+        assert_eq!(app.game_config, GameConfig::default());
+
+        let (x, y) = app.game.start_spot().expect("A board always has a safe start");
+        assert_eq!(app.game.reveal(x, y), StepResult::Phew);
+        app.game.flag(0, 0);
+        app.handle_game_events();
+
+        assert_eq!(app.game.state(), GameState::Stopped(true));
+        assert!(app.records.high_scores.scores(&config).is_empty());
+        assert!(app.records.high_scores.scores(&app.game_config).is_empty());
+        assert!(app.records.statistics.table(&app.game_config).is_none());
+
+        // The next game is played with the player's own config
+        app.refresh();
+        assert_eq!(*app.game.config(), GameConfig::default());
+    }
+}
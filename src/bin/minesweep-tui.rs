@@ -0,0 +1,482 @@
+//! Terminal frontend, to play over SSH. It plays the same games as the egui app, with the same config, high scores
+//! and statistics, which it reads from (and writes back to) the file where the egui app keeps them.
+
+#[cfg(unix)]
+fn main() {
+    tui::run();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The terminal frontend only runs on Unix terminals");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+mod tui {
+    use minesweep_rs::app::MinesweepRsApp;
+    use minesweep_rs::game::{Game, GameConfig, GameDifficulty, GameEvent, GameState, SavedGame};
+    use minesweep_rs::minefield::{Heading, Spot, SpotLook, SpotState, Topology};
+    use minesweep_rs::records::{FinishedGame, Records};
+    use minesweep_rs::settings_file::SettingsFile;
+    use std::io::{Read, Write};
+
+    pub fn run() {
+        let Some(mut storage) = SettingsFile::open() else {
+            eprintln!("Could not read the data saved by the egui app. Try again in a moment");
+            std::process::exit(1);
+        };
+        let mut app = TuiApp::new(&storage);
+
+        {
+            let terminal = match Terminal::enter() {
+                Some(terminal) => terminal,
+                None => {
+                    eprintln!("Not a terminal");
+                    std::process::exit(1);
+                }
+            };
+
+            while !app.quit {
+                terminal.draw(&app.render());
+
+                for input in terminal.read_input() {
+                    app.handle_input(input);
+                }
+                app.handle_game_events();
+            }
+        }
+
+        // The egui app may have recorded games of its own in the meantime
+        storage.reload();
+        app.save(&mut storage);
+        eframe::Storage::flush(&mut storage);
+    }
+
+    /// The terminal, in raw mode and on the alternate screen, with mouse reporting on. Everything is put back as it
+    /// was when this is dropped.
+    struct Terminal {
+        original: libc::termios,
+    }
+
+    impl Terminal {
+        /// Reads from the terminal give up after this many tenths of a second, so the stopwatch keeps ticking
+        const READ_TIMEOUT: libc::cc_t = 1;
+
+        fn enter() -> Option<Self> {
+            // SAFETY: `termios` is plain data, and is only used after `tcgetattr` fills it in
+            let mut original: libc::termios = unsafe { std::mem::zeroed() };
+            if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+                return None;
+            }
+
+            let mut raw = original;
+            unsafe { libc::cfmakeraw(&mut raw) };
+            raw.c_cc[libc::VMIN] = 0;
+            raw.c_cc[libc::VTIME] = Self::READ_TIMEOUT;
+            if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) } != 0 {
+                return None;
+            }
+
+            // Alternate screen, hidden cursor, and mouse button reports in SGR format
+            Self::write("\x1b[?1049h\x1b[?25l\x1b[?1000h\x1b[?1006h");
+
+            Some(Self { original })
+        }
+
+        fn write(text: &str) {
+            let mut stdout = std::io::stdout().lock();
+            let _ = stdout.write_all(text.as_bytes());
+            let _ = stdout.flush();
+        }
+
+        /// Draw a whole screen, given as lines of text with escape codes
+        fn draw(&self, lines: &[String]) {
+            let mut screen = String::from("\x1b[H");
+            for line in lines {
+                screen.push_str(line);
+                screen.push_str("\x1b[0m\x1b[K\r\n");
+            }
+            screen.push_str("\x1b[J");
+
+            Self::write(&screen);
+        }
+
+        /// Wait a little for input, and return whatever was typed or clicked
+        fn read_input(&self) -> Vec<Input> {
+            let mut buffer = [0; 256];
+            match std::io::stdin().lock().read(&mut buffer) {
+                Ok(read) => Input::parse(&buffer[..read]),
+                Err(_) => vec![],
+            }
+        }
+    }
+
+    impl Drop for Terminal {
+        fn drop(&mut self) {
+            Self::write("\x1b[?1006l\x1b[?1000l\x1b[?25h\x1b[?1049l");
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original) };
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Input {
+        Char(char),
+        Up,
+        Down,
+        Left,
+        Right,
+        /// A mouse button was pressed at the given screen column and row (starting from `1`)
+        Click {
+            button: MouseButton,
+            column: u16,
+            row: u16,
+        },
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum MouseButton {
+        Primary,
+        Middle,
+        Secondary,
+    }
+
+    impl Input {
+        /// Parse the bytes read from the terminal. Unknown escape sequences are skipped.
+        fn parse(bytes: &[u8]) -> Vec<Input> {
+            let text = String::from_utf8_lossy(bytes);
+            let mut chars = text.chars().peekable();
+            let mut inputs = vec![];
+
+            while let Some(c) = chars.next() {
+                if c != '\x1b' {
+                    inputs.push(Input::Char(c));
+                    continue;
+                }
+
+                // A lone escape
+                if chars.next_if(|c| *c == '[' || *c == 'O').is_none() {
+                    continue;
+                }
+
+                let mut sequence = String::new();
+                for c in chars.by_ref() {
+                    sequence.push(c);
+                    if c.is_ascii_alphabetic() || c == '~' {
+                        break;
+                    }
+                }
+
+                match sequence.as_str() {
+                    "A" => inputs.push(Input::Up),
+                    "B" => inputs.push(Input::Down),
+                    "C" => inputs.push(Input::Right),
+                    "D" => inputs.push(Input::Left),
+                    _ => inputs.extend(Self::parse_mouse(&sequence)),
+                }
+            }
+
+            inputs
+        }
+
+        /// Parse a mouse report in SGR format (`<button;column;rowM`), keeping only button presses
+        fn parse_mouse(sequence: &str) -> Option<Input> {
+            let report = sequence.strip_prefix('<')?.strip_suffix('M')?;
+            let mut fields = report.split(';').map(|field| field.parse::<u16>().ok());
+            let (button, column, row) = (fields.next()??, fields.next()??, fields.next()??);
+
+            let button = match button {
+                0 => MouseButton::Primary,
+                1 => MouseButton::Middle,
+                2 => MouseButton::Secondary,
+                // Motion, scrolling, and buttons with modifiers
+                _ => return None,
+            };
+
+            Some(Input::Click { button, column, row })
+        }
+    }
+
+    struct TuiApp {
+        game_config: GameConfig,
+        game: Game,
+        cursor: (u16, u16),
+        player_name: String,
+        records: Records,
+        /// What happened last, shown below the minefield
+        message: Option<String>,
+        quit: bool,
+    }
+
+    impl TuiApp {
+        /// Columns left of the minefield
+        const MARGIN: u16 = 2;
+        /// Rows above the minefield
+        const HEADER_ROWS: u16 = 2;
+        const HELP: &str = "arrows/hjkl move  space reveal  f flag  c chord  u undo  y redo  p pause  n new game  \
+                            1/2/3 difficulty  q quit";
+        const HIDDEN_SPOT: &str = "·";
+        const FLAG: &str = "F";
+        const MINE: &str = "*";
+        const RESET: &str = "\x1b[0m";
+        const REVERSE: &str = "\x1b[7m";
+        const GRAY: &str = "\x1b[90m";
+        const RED: &str = "\x1b[91m";
+        const GREEN: &str = "\x1b[92m";
+        const YELLOW: &str = "\x1b[93m";
+        const EXPLODED: &str = "\x1b[97;41m";
+        const START_SPOT: &str = "\x1b[97;44m";
+        /// Colors of the numbers of neighboring mines, which repeat for spots with more than one mine
+        const NUMBER_COLORS: [&str; 8] = [
+            "\x1b[94m", "\x1b[32m", "\x1b[91m", "\x1b[35m", "\x1b[33m", "\x1b[36m", "\x1b[97m", "\x1b[37m",
+        ];
+
+        fn new(storage: &SettingsFile) -> Self {
+            let mut game_config: GameConfig = eframe::get_value(storage, MinesweepRsApp::APP_NAME).unwrap_or_default();
+            if !game_config.is_valid() {
+                game_config = GameConfig::default();
+            }
+
+            // Carry on with the game the egui app left off, if there is one
+            let game = eframe::get_value::<SavedGame>(storage, MinesweepRsApp::SAVED_GAME_KEY)
+                .and_then(|saved_game| Game::resume(game_config, saved_game))
+                .unwrap_or_else(|| Game::new(game_config));
+
+            Self {
+                game_config,
+                cursor: (game.minefield().width() / 2, game.minefield().height() / 2),
+                game,
+                player_name: eframe::get_value(storage, MinesweepRsApp::PLAYER_NAME_KEY)
+                    .unwrap_or_else(|| MinesweepRsApp::DEFAULT_PLAYER_NAME.to_owned()),
+                records: Records::load(storage),
+                message: None,
+                quit: false,
+            }
+        }
+
+        /// Save to storage, adding the games finished here to the records which are already in it
+        fn save(&mut self, storage: &mut SettingsFile) {
+            eframe::set_value(storage, MinesweepRsApp::APP_NAME, &self.game_config);
+            eframe::set_value(storage, MinesweepRsApp::SAVED_GAME_KEY, &self.game.saved());
+
+            self.records.merge_stored(storage);
+            self.records.save(storage);
+        }
+
+        fn new_game(&mut self) {
+            self.game = Game::new(self.game_config);
+            self.cursor = (self.game.minefield().width() / 2, self.game.minefield().height() / 2);
+            self.message = None;
+        }
+
+        fn handle_input(&mut self, input: Input) {
+            let (x, y) = self.cursor;
+            let minefield = self.game.minefield();
+
+            match input {
+                Input::Up | Input::Char('k') => self.cursor = minefield.next_coords(x, y, Heading::Up),
+                Input::Down | Input::Char('j') => self.cursor = minefield.next_coords(x, y, Heading::Down),
+                Input::Left | Input::Char('h') => self.cursor = minefield.next_coords(x, y, Heading::Left),
+                Input::Right | Input::Char('l') => self.cursor = minefield.next_coords(x, y, Heading::Right),
+                Input::Char(' ') | Input::Char('\r') => self.reveal_or_chord(x, y),
+                Input::Char('f') => {
+                    self.game.flag(x, y);
+                }
+                Input::Char('c') => {
+                    self.game.chord(x, y);
+                }
+                Input::Char('u') => self.game.undo(),
+                Input::Char('y') => self.game.redo(),
+                Input::Char('p') if self.game.is_paused() => self.game.unpause(),
+                Input::Char('p') => self.game.pause(),
+                Input::Char('n') => self.new_game(),
+                Input::Char(difficulty @ '1'..='3') => {
+                    let preset = match difficulty {
+                        '1' => GameDifficulty::EASY,
+                        '2' => GameDifficulty::MEDIUM,
+                        _ => GameDifficulty::HARD,
+                    };
+                    self.game_config = self.game_config.with_board_of(&preset);
+                    self.new_game();
+                }
+                // Ctrl+C
+                Input::Char('q') | Input::Char('\x03') => self.quit = true,
+                Input::Click { button, column, row } => {
+                    if let Some((x, y)) = self.spot_at(column, row) {
+                        self.cursor = (x, y);
+                        match button {
+                            MouseButton::Primary => self.reveal_or_chord(x, y),
+                            MouseButton::Middle => {
+                                self.game.chord(x, y);
+                            }
+                            MouseButton::Secondary => {
+                                self.game.flag(x, y);
+                            }
+                        }
+                    }
+                }
+                Input::Char(_) => {}
+            }
+        }
+
+        /// Step on a hidden spot, or chord on a revealed one
+        fn reveal_or_chord(&mut self, x: u16, y: u16) {
+            match self.game.minefield().spot(x, y).unwrap().state {
+                SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine => {
+                    self.game.reveal(x, y);
+                }
+                SpotState::RevealedEmpty { neighboring_mines: _ } => {
+                    self.game.chord(x, y);
+                }
+                _ => self.game.waste_click(),
+            }
+        }
+
+        /// React to what happened in the game, since the last input
+        fn handle_game_events(&mut self) {
+            for event in self.game.take_events() {
+                match event {
+                    GameEvent::Finished { .. } => self.game_over(),
+                    GameEvent::Revived => {
+                        self.records.remove_last_replay();
+                        self.message = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        fn game_over(&mut self) {
+            if let Some(rank) = self.records.add(&FinishedGame::of(&self.game, &self.player_name)) {
+                self.message = Some(format!("New high score, rank {}!", rank + 1));
+            }
+        }
+
+        /// Width of a spot on screen, in columns
+        fn spot_width(&self) -> u16 {
+            match self.game.minefield().topology() {
+                Topology::Hex => 4,
+                Topology::Square | Topology::Torus => 3,
+            }
+        }
+
+        /// Columns the given row of spots is shifted right by
+        fn row_offset(&self, y: u16) -> u16 {
+            match self.game.minefield().topology() {
+                // Odd rows are shifted right by half a spot
+                Topology::Hex if y % 2 == 1 => self.spot_width() / 2,
+                _ => 0,
+            }
+        }
+
+        /// Coordinates of the spot drawn at the given screen column and row (starting from `1`)
+        fn spot_at(&self, column: u16, row: u16) -> Option<(u16, u16)> {
+            let y = row.checked_sub(1 + Self::HEADER_ROWS)?;
+            let x = column.checked_sub(1 + Self::MARGIN + self.row_offset(y))? / self.spot_width();
+
+            (x < self.game.minefield().width() && y < self.game.minefield().height()).then_some((x, y))
+        }
+
+        fn render(&self) -> Vec<String> {
+            let game = &self.game;
+            let mut lines = vec![];
+
+            let time = game.millis() as f64 / 1000.0;
+            lines.push(format!(
+                "{}minesweep-rs{}  {}  Mines {}  Flags {}{}{}  Time {:.1}",
+                Self::REVERSE,
+                Self::RESET,
+                MinesweepRsApp::config_name(&self.game_config),
+                self.game_config.mines,
+                if game.placed_flags() > self.game_config.mines {
+                    Self::RED
+                } else {
+                    ""
+                },
+                game.placed_flags(),
+                Self::RESET,
+                time,
+            ));
+            lines.push(String::new());
+
+            let finished = match game.state() {
+                GameState::Stopped(is_won) => Some(is_won),
+                GameState::Ready | GameState::Running => None,
+            };
+
+            for y in 0..game.minefield().height() {
+                let mut line = " ".repeat((Self::MARGIN + self.row_offset(y)) as usize);
+                for x in 0..game.minefield().width() {
+                    let spot = game.minefield().spot(x, y).unwrap();
+
+                    // The minefield is hidden while the game is paused
+                    let (text, mut color) = if game.is_paused() {
+                        (Self::HIDDEN_SPOT.to_owned(), Self::GRAY)
+                    } else {
+                        Self::spot_look(spot, finished.is_some())
+                    };
+
+                    if game.start_spot() == Some((x, y)) && game.minefield().mines() == 0 {
+                        color = Self::START_SPOT;
+                    }
+
+                    let cursor = if self.cursor == (x, y) && finished.is_none() {
+                        Self::REVERSE
+                    } else {
+                        ""
+                    };
+                    let text = format!("{:^width$}", text, width = self.spot_width() as usize);
+                    line.push_str(&format!("{}{}{}{}", color, cursor, text, Self::RESET));
+                }
+                lines.push(line);
+            }
+
+            lines.push(String::new());
+
+            let status = match (game.state(), game.is_paused()) {
+                (_, true) => "Paused. Press p to continue".to_owned(),
+                (GameState::Ready, _) if game.start_spot().is_some() => {
+                    "Ready. Start on the highlighted spot".to_owned()
+                }
+                (GameState::Running, _) if game.start_spot().is_some() && game.minefield().mines() == 0 => {
+                    "Start on the highlighted spot".to_owned()
+                }
+                (GameState::Ready, _) => "Ready".to_owned(),
+                (GameState::Running, _) if game.may_need_guessing() => {
+                    format!(
+                        "Running. {}This minefield may need guessing{}",
+                        Self::YELLOW,
+                        Self::RESET
+                    )
+                }
+                (GameState::Running, _) => "Running".to_owned(),
+                (GameState::Stopped(true), _) => format!("{}You won!{}", Self::GREEN, Self::RESET),
+                (GameState::Stopped(false), _) => format!("{}You lost!{}", Self::RED, Self::RESET),
+            };
+            lines.push(match &self.message {
+                Some(message) => format!("{}  {}", status, message),
+                None => status,
+            });
+            lines.push(format!("{}{}{}", Self::GRAY, Self::HELP, Self::RESET));
+
+            lines
+        }
+
+        /// Text and color of a spot. The mines and wrong flags are shown once the game is finished.
+        fn spot_look(spot: &Spot, is_finished: bool) -> (String, &'static str) {
+            match spot.look(is_finished) {
+                SpotLook::Hidden => (Self::HIDDEN_SPOT.to_owned(), Self::GRAY),
+                SpotLook::Revealed(0) => (" ".to_owned(), ""),
+                SpotLook::Revealed(neighboring_mines) => {
+                    let color = Self::NUMBER_COLORS[(neighboring_mines as usize - 1) % Self::NUMBER_COLORS.len()];
+                    (neighboring_mines.to_string(), color)
+                }
+                SpotLook::Flags(flags) => (SpotLook::counted(Self::FLAG, flags), Self::YELLOW),
+                SpotLook::CheckedFlags { flags, is_right: true } => (SpotLook::counted(Self::FLAG, flags), Self::GREEN),
+                SpotLook::CheckedFlags { flags, is_right: false } => (SpotLook::counted(Self::FLAG, flags), Self::RED),
+                SpotLook::Mines(mines) => (SpotLook::counted(Self::MINE, mines), Self::RED),
+                SpotLook::Exploded(mines) => (SpotLook::counted(Self::MINE, mines), Self::EXPLODED),
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::game::{FirstClick, GameConfig, Generator, PlayMode, Seed};
+use crate::minefield::{Minefield, SpotState, Topology};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// A minefield which can be passed on to other players, as a short code: the game config, where the mines are, and
+/// the spot where the game starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    pub config: GameConfig,
+
+    /// Coordinates of the mines, repeated for spots which hold more than one mine
+    pub mines: Vec<(u16, u16)>,
+
+    /// Spot where the game was started, which is never a mine
+    pub start: Option<(u16, u16)>,
+}
+
+impl Board {
+    /// Version of the code format, which comes first in every code
+    const VERSION: u8 = 1;
+
+    /// Marks a board without a start spot
+    const NO_START: u8 = u8::MAX;
+
+    /// The board of a minefield whose mines have been placed
+    pub fn of(config: &GameConfig, minefield: &Minefield, start: Option<(u16, u16)>) -> Self {
+        let mut mines: Vec<(u16, u16)> = minefield.mines_coords().collect();
+        mines.sort_by_key(|(x, y)| (*y, *x));
+
+        Self {
+            config: *config,
+            mines,
+            start,
+        }
+    }
+
+    /// The minefield described by this board
+    pub fn minefield(&self) -> Minefield {
+        self.config.empty_minefield().with_mines_at(&self.mines)
+    }
+
+    /// Spot where a game on this board starts, which is never a mine: the start spot of the board if it has one, or
+    /// else the first spot (in reading order) with no neighboring mines, or else the first empty spot
+    pub fn safe_start(&self) -> Option<(u16, u16)> {
+        if self.start.is_some() {
+            return self.start;
+        }
+
+        let minefield = self.minefield();
+        let reading_order = || (0..self.config.height).flat_map(|y| (0..self.config.width).map(move |x| (x, y)));
+        let state = |(x, y): (u16, u16)| minefield.spot(x, y).map(|spot| spot.state);
+
+        reading_order()
+            .find(|coords| state(*coords) == Some(SpotState::HiddenEmpty { neighboring_mines: 0 }))
+            .or_else(|| reading_order().find(|coords| matches!(state(*coords), Some(SpotState::HiddenEmpty { .. }))))
+    }
+
+    /// Encode the board as a short code, which only uses characters that are safe in a URL
+    pub fn to_code(&self) -> String {
+        let config = &self.config;
+        let (start_x, start_y) = self
+            .start
+            .map(|(x, y)| (x as u8, y as u8))
+            .unwrap_or((Self::NO_START, Self::NO_START));
+
+        let mut bytes = vec![
+            Self::VERSION,
+            config.width as u8,
+            config.height as u8,
+            config.mines_per_spot,
+            config.first_click as u8,
+            config.generator as u8,
+            config.play_mode as u8,
+            config.topology as u8,
+            start_x,
+            start_y,
+        ];
+
+        // The number of mines in each spot, in reading order, packed in as few bits as they need
+        let bits = Self::bits_per_spot(config.mines_per_spot);
+        let mut packed = vec![0u8; (config.width as usize * config.height as usize * bits).div_ceil(8)];
+        for (x, y) in &self.mines {
+            let bit = (*y as usize * config.width as usize + *x as usize) * bits;
+            packed[bit / 8] += 1 << (bit % 8);
+        }
+        bytes.extend(packed);
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Decode a board which was encoded with `to_code`
+    pub fn from_code(code: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|e| format!("Not a valid board code: {}", e))?;
+
+        let [version, width, height, mines_per_spot, first_click, generator, play_mode, topology, start_x, start_y, packed @ ..] =
+            bytes.as_slice()
+        else {
+            return Err("Not a valid board code: too short".to_owned());
+        };
+
+        if *version != Self::VERSION {
+            return Err(format!("Not a valid board code: unknown version {}", version));
+        }
+
+        let mut config = GameConfig {
+            width: *width as u16,
+            height: *height as u16,
+            mines: 0,
+            first_click: match first_click {
+                0 => FirstClick::Safe,
+                1 => FirstClick::Opening,
+                _ => return Err(format!("Not a valid board code: unknown first click {}", first_click)),
+            },
+            generator: match generator {
+                0 => Generator::Random,
+                1 => Generator::NoGuess,
+                _ => return Err(format!("Not a valid board code: unknown generator {}", generator)),
+            },
+            play_mode: match play_mode {
+                0 => PlayMode::Ranked,
+                1 => PlayMode::Practice,
+                _ => return Err(format!("Not a valid board code: unknown play mode {}", play_mode)),
+            },
+            topology: match topology {
+                0 => Topology::Square,
+                1 => Topology::Hex,
+                2 => Topology::Torus,
+                _ => return Err(format!("Not a valid board code: unknown topology {}", topology)),
+            },
+            mines_per_spot: *mines_per_spot,
+            // The board is already laid out
+            seed: Seed::Random,
+        };
+
+        if !(1..=GameConfig::MAX_MINES_PER_SPOT).contains(&config.mines_per_spot) {
+            return Err(format!(
+                "Not a valid board code: {} mines per spot",
+                config.mines_per_spot
+            ));
+        }
+
+        let bits = Self::bits_per_spot(config.mines_per_spot);
+        let spot_count = config.width as usize * config.height as usize;
+        if packed.len() != (spot_count * bits).div_ceil(8) {
+            return Err("Not a valid board code: wrong number of spots".to_owned());
+        }
+
+        let mut mines = vec![];
+        for index in 0..spot_count {
+            let bit = index * bits;
+            let spot_mines = (packed[bit / 8] >> (bit % 8)) & ((1 << bits) - 1);
+            if spot_mines > config.mines_per_spot {
+                return Err("Not a valid board code: too many mines in a spot".to_owned());
+            }
+
+            let coords = (
+                (index % config.width as usize) as u16,
+                (index / config.width as usize) as u16,
+            );
+            mines.extend(std::iter::repeat_n(coords, spot_mines as usize));
+        }
+        config.mines = mines.len() as u32;
+
+        let start = match (*start_x, *start_y) {
+            (Self::NO_START, Self::NO_START) => None,
+            (x, y) => Some((x as u16, y as u16)),
+        };
+
+        if !config.is_valid() {
+            return Err(format!("Not a valid board config: {:?}", config));
+        }
+
+        if let Some((x, y)) = start {
+            if x >= config.width || y >= config.height || mines.contains(&(x, y)) {
+                return Err("Not a valid board code: the start spot is not safe".to_owned());
+            }
+        }
+
+        Ok(Self { config, mines, start })
+    }
+
+    /// Number of bits needed for the number of mines in one spot
+    fn bits_per_spot(mines_per_spot: u8) -> usize {
+        (u8::BITS - mines_per_spot.leading_zeros()).max(1) as usize
+    }
+}
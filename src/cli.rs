@@ -0,0 +1,199 @@
+use crate::game::{GameConfig, GameDifficulty, Seed};
+
+/// Command-line arguments of the native app
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    pub difficulty: Option<GameDifficulty>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    pub mines: Option<u32>,
+    pub seed: Option<Seed>,
+    pub theme: Option<Theme>,
+    pub fullscreen: bool,
+
+    /// Start from the default config, instead of the one in storage
+    pub reset_config: bool,
+
+    /// Print the usage and exit, instead of running the app
+    pub help: bool,
+}
+
+/// Theme of the native window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+
+    /// Follow the theme of the system
+    System,
+}
+
+impl Args {
+    pub const USAGE: &str = "\
+Usage: minesweep-rs [OPTIONS]
+
+Options:
+  --difficulty <easy|medium|hard>  Play a preset minefield
+  --width <N>                      Width of a custom minefield
+  --height <N>                     Height of a custom minefield
+  --mines <N>                      Number of mines in a custom minefield
+  --seed <N|daily|random>          Lay the mines with a fixed seed, the daily seed, or a random seed for each game
+  --theme <dark|light|system>      Theme of the window
+  --fullscreen                     Start in fullscreen
+  --reset-config                   Forget the saved config, and start from the default one
+  -h, --help                       Print this help
+
+Options which are not given are taken from the saved config. The saved number of mines is lowered if it doesn't fit in
+the minefield.";
+
+    /// Parse the arguments which follow the program name. Values can be given as `--option value` or
+    /// `--option=value`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => (option.to_owned(), Some(value.to_owned())),
+                _ => (arg, None),
+            };
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", option))
+            };
+
+            match option.as_str() {
+                "-h" | "--help" | "--fullscreen" | "--reset-config" if inline_value.is_some() => {
+                    return Err(format!("{} takes no value", option));
+                }
+                "-h" | "--help" => parsed.help = true,
+                "--fullscreen" => parsed.fullscreen = true,
+                "--reset-config" => parsed.reset_config = true,
+                "--difficulty" => {
+                    parsed.difficulty = Some(match value()?.to_lowercase().as_str() {
+                        "easy" => GameDifficulty::Easy,
+                        "medium" => GameDifficulty::Medium,
+                        "hard" => GameDifficulty::Hard,
+                        other => return Err(format!("Unknown difficulty '{}', expected easy, medium or hard", other)),
+                    });
+                }
+                "--width" => {
+                    parsed.width = Some(Self::number(
+                        &option,
+                        &value()?,
+                        GameConfig::MIN_WIDTH,
+                        GameConfig::MAX_WIDTH,
+                    )?);
+                }
+                "--height" => {
+                    parsed.height = Some(Self::number(
+                        &option,
+                        &value()?,
+                        GameConfig::MIN_HEIGHT,
+                        GameConfig::MAX_HEIGHT,
+                    )?);
+                }
+                "--mines" => {
+                    let max_mines = GameConfig {
+                        width: GameConfig::MAX_WIDTH,
+                        height: GameConfig::MAX_HEIGHT,
+                        mines_per_spot: GameConfig::MAX_MINES_PER_SPOT,
+                        ..GameConfig::default()
+                    }
+                    .max_mines();
+                    parsed.mines = Some(Self::number(&option, &value()?, GameConfig::MIN_MINES, max_mines)?);
+                }
+                "--seed" => {
+                    parsed.seed = Some(match value()?.to_lowercase().as_str() {
+                        "daily" => Seed::Daily,
+                        "random" => Seed::Random,
+                        seed => Seed::Fixed(Self::number(&option, seed, 0, u64::MAX)?),
+                    });
+                }
+                "--theme" => {
+                    parsed.theme = Some(match value()?.to_lowercase().as_str() {
+                        "dark" => Theme::Dark,
+                        "light" => Theme::Light,
+                        "system" => Theme::System,
+                        other => return Err(format!("Unknown theme '{}', expected dark, light or system", other)),
+                    });
+                }
+                _ => return Err(format!("Unknown argument '{}'", option)),
+            }
+        }
+
+        // Mines which don't fit in the minefield, however many mines a spot can hold, are known to be wrong before the
+        // saved config is loaded
+        let is_sized = parsed.difficulty.is_some() || (parsed.width.is_some() && parsed.height.is_some());
+        if is_sized && parsed.mines.is_some() {
+            let most_mines = GameConfig {
+                mines_per_spot: GameConfig::MAX_MINES_PER_SPOT,
+                ..GameConfig::default()
+            };
+            parsed.game_config(most_mines)?;
+        }
+
+        Ok(parsed)
+    }
+
+    /// The game config the arguments ask for, based on the `saved` config. Returns `None` if they don't change the
+    /// config, so that the saved game can be continued, or an error if the number of mines given doesn't fit in the
+    /// minefield.
+    pub fn game_config(&self, saved: GameConfig) -> Result<Option<GameConfig>, String> {
+        let changes_config = self.reset_config
+            || self.difficulty.is_some()
+            || self.width.is_some()
+            || self.height.is_some()
+            || self.mines.is_some()
+            || self.seed.is_some();
+        if !changes_config {
+            return Ok(None);
+        }
+
+        let mut config = if self.reset_config {
+            GameConfig::default()
+        } else {
+            saved
+        };
+
+        match self.difficulty {
+            Some(GameDifficulty::Easy) => config = config.with_board_of(&GameDifficulty::EASY),
+            Some(GameDifficulty::Medium) => config = config.with_board_of(&GameDifficulty::MEDIUM),
+            Some(GameDifficulty::Hard) => config = config.with_board_of(&GameDifficulty::HARD),
+            Some(GameDifficulty::Custom) | None => {}
+        }
+
+        config.width = self.width.unwrap_or(config.width);
+        config.height = self.height.unwrap_or(config.height);
+        config.mines = self.mines.unwrap_or(config.mines.min(config.max_mines()));
+        config.seed = self.seed.unwrap_or(config.seed);
+
+        if !config.is_valid() {
+            return Err(format!(
+                "--mines must be at most {} for a {}x{} minefield",
+                config.max_mines(),
+                config.width,
+                config.height
+            ));
+        }
+
+        Ok(Some(config))
+    }
+
+    /// Parse the value of a numeric option, which must be within the given range
+    fn number<T>(option: &str, value: &str, min: T, max: T) -> Result<T, String>
+    where
+        T: std::str::FromStr + PartialOrd + std::fmt::Display + Copy,
+    {
+        match value.parse::<T>() {
+            Ok(number) if (min..=max).contains(&number) => Ok(number),
+            _ => Err(format!(
+                "{} must be a number between {} and {}, not '{}'",
+                option, min, max, value
+            )),
+        }
+    }
+}
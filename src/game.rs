@@ -0,0 +1,963 @@
+use crate::board::Board;
+use crate::minefield::{FlagToggleResult, Minefield, StepResult, Topology};
+use crate::replay::{Action, Replay};
+use crate::solver::{self, Deduction};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// A game of minesweeper, without any user interface: the rules, the stopwatch, and the record of everything the
+/// player did. A frontend makes the player's moves, and then shows the state of the game, or reacts to the events the
+/// moves caused.
+pub struct Game {
+    config: GameConfig,
+    minefield: Minefield,
+    state: GameState,
+    stopwatch: Stopwatch,
+    placed_flags: u32,
+    hints_used: u32,
+    /// Practice games, and games with undone moves, don't count for the high scores and statistics
+    is_practice: bool,
+    /// Is the running game paused? The stopwatch is stopped, and no moves can be made.
+    is_paused: bool,
+    /// Number of clicks in the game, including wasted clicks which changed nothing
+    clicks: u32,
+    /// Number of clicks which revealed or flagged spots
+    effective_clicks: u32,
+    /// 3BV of the minefield, known once the mines are placed
+    bbbv: u32,
+    /// Seed of the random number generator which lays the mines
+    seed: u64,
+    /// Spot where a seeded game (or a game on a board which was passed on) starts, which is never a mine. Every player
+    /// of the seed gets the same minefield by starting there.
+    start_spot: Option<(u16, u16)>,
+    /// Board the game is played on, if it was laid out beforehand
+    board: Option<Board>,
+    /// Was the minefield meant to be solvable without guessing, but none was found in time?
+    may_need_guessing: bool,
+    replay: Replay,
+    undo_history: Vec<Snapshot>,
+    redo_history: Vec<Snapshot>,
+    /// What happened since the events were last taken
+    events: Vec<GameEvent>,
+}
+
+impl Game {
+    /// Time spent trying minefields, before giving up on generating one which can be solved without guessing. The
+    /// first step waits for it, so it must stay short enough for the app not to look frozen.
+    const NO_GUESS_MILLIS: u64 = 500;
+
+    /// Number of spots in all the minefields tried for a seeded game, before giving up on generating one which can be
+    /// solved without guessing. Seeded games can't be limited in time, or the minefield would depend on the speed of
+    /// the machine. Solving takes time in proportion to the spots, so this takes about as long as `NO_GUESS_MILLIS`.
+    const NO_GUESS_SEEDED_SPOTS: usize = 25_000;
+
+    /// A new game, played as described by the config. The mines are placed when the first step is taken.
+    pub fn new(config: GameConfig) -> Self {
+        let seed = config.seed.new_game_seed();
+
+        Self {
+            config,
+            minefield: config.empty_minefield(),
+            state: GameState::Ready,
+            stopwatch: Stopwatch::default(),
+            placed_flags: 0,
+            hints_used: 0,
+            is_practice: config.play_mode == PlayMode::Practice,
+            is_paused: false,
+            clicks: 0,
+            effective_clicks: 0,
+            bbbv: 0,
+            seed,
+            start_spot: Self::seeded_start_spot(&config, seed),
+            board: None,
+            may_need_guessing: false,
+            replay: Replay::new(config, ""),
+            undo_history: vec![],
+            redo_history: vec![],
+            events: vec![],
+        }
+    }
+
+    /// A new game on a board which was passed on
+    pub fn with_board(board: Board) -> Self {
+        let mut game = Self::new(board.config);
+        game.start_spot = board.safe_start();
+        game.board = Some(board);
+        game
+    }
+
+    /// Continue a saved game, which must have been played with the given config, unless it was played on a board which
+    /// was passed on, with the board's config. Returns `None` if it wasn't, which only happens if storage was tampered
+    /// with. A running game is resumed paused, to give the player a chance to look at it before the stopwatch starts
+    /// again.
+    pub fn resume(config: GameConfig, saved_game: SavedGame) -> Option<Self> {
+        let board = saved_game.board.as_deref().and_then(|code| Board::from_code(code).ok());
+        let config = board.as_ref().map_or(config, |board| board.config);
+
+        // Mines may not have been placed yet, if the game was saved before the first step
+        let minefield = &saved_game.minefield;
+        if minefield.width() != config.width
+            || minefield.height() != config.height
+            || minefield.topology() != config.topology
+            || minefield.mines_per_spot() != config.mines_per_spot
+            || minefield.spots().count() != minefield.width() as usize * minefield.height() as usize
+            || (minefield.mines() != 0 && minefield.mines() != config.mines)
+        {
+            return None;
+        }
+
+        tracing::debug!("Resuming saved game {:?}", saved_game.game_state);
+
+        let mut game = Self::new(config);
+
+        game.minefield = saved_game.minefield;
+        game.placed_flags = saved_game.placed_flags;
+        game.state = saved_game.game_state;
+        game.hints_used = saved_game.hints_used;
+        game.is_practice = saved_game.is_practice;
+        game.clicks = saved_game.clicks;
+        game.effective_clicks = saved_game.effective_clicks;
+        game.may_need_guessing = saved_game.may_need_guessing;
+        if game.minefield.mines() > 0 {
+            game.bbbv = game.minefield.bbbv();
+        }
+        game.stopwatch.millis_before = saved_game.millis;
+
+        game.replay = saved_game.replay;
+
+        // A daily game started yesterday is still played on yesterday's minefield
+        game.seed = saved_game.seed;
+        game.start_spot = Self::seeded_start_spot(&config, saved_game.seed);
+
+        if let Some(board) = board {
+            game.start_spot = board.safe_start();
+            game.board = Some(board);
+        }
+
+        if game.state == GameState::Running {
+            game.is_paused = true;
+        }
+
+        Some(game)
+    }
+
+    /// The game, as it should be saved in storage to be resumed later
+    pub fn saved(&self) -> SavedGame {
+        SavedGame {
+            minefield: self.minefield.clone(),
+            placed_flags: self.placed_flags,
+            game_state: self.state,
+            hints_used: self.hints_used,
+            is_practice: self.is_practice,
+            clicks: self.clicks,
+            effective_clicks: self.effective_clicks,
+            replay: self.replay.clone(),
+            millis: self.stopwatch.millis(),
+            seed: self.seed,
+            board: self.board.as_ref().map(Board::to_code),
+            may_need_guessing: self.may_need_guessing,
+        }
+    }
+
+    /// Step on a hidden spot. The first step places the mines, and must be taken on the start spot if there is one.
+    pub fn reveal(&mut self, x: u16, y: u16) -> StepResult {
+        if !self.can_move() {
+            return StepResult::Invalid;
+        }
+
+        // Only the start spot is known to be safe, since the minefield is laid out the same wherever the game starts
+        if self.minefield.mines() == 0 && self.start_spot.is_some_and(|start_spot| start_spot != (x, y)) {
+            return StepResult::Invalid;
+        }
+
+        let snapshot = self.snapshot_before_move();
+
+        if self.minefield.mines() == 0 {
+            // Laying the mines may take a while, which doesn't count in the player's time
+            let is_running = self.state == GameState::Running;
+            self.stopwatch.stop();
+            self.place_mines(x, y);
+            if is_running {
+                self.stopwatch.start();
+            }
+        }
+
+        self.start();
+        self.replay.record(self.stopwatch.millis(), Action::Step(x, y));
+        self.clicks += 1;
+
+        let step_result = self.minefield.step(x, y);
+        if step_result != StepResult::Invalid {
+            self.effective_clicks += 1;
+            self.remember_move(snapshot);
+        }
+
+        self.events.push(GameEvent::Revealed {
+            x,
+            y,
+            result: step_result,
+        });
+        self.check_finished(step_result);
+
+        step_result
+    }
+
+    /// Step on all the hidden neighbors of a revealed spot, if it has enough flags around it
+    pub fn chord(&mut self, x: u16, y: u16) -> StepResult {
+        if !self.can_move() {
+            return StepResult::Invalid;
+        }
+
+        self.start();
+        self.replay.record(self.stopwatch.millis(), Action::AutoStep(x, y));
+        self.clicks += 1;
+        let snapshot = self.snapshot_before_move();
+
+        // Chording with all the neighbors already revealed or flagged changes nothing
+        let has_hidden_neighbors = self.minefield.has_hidden_neighbors(x, y);
+
+        let step_result = self.minefield.auto_step(x, y);
+        if step_result != StepResult::Invalid && has_hidden_neighbors {
+            self.effective_clicks += 1;
+            self.remember_move(snapshot);
+        }
+
+        self.events.push(GameEvent::Chorded {
+            x,
+            y,
+            result: step_result,
+        });
+        self.check_finished(step_result);
+
+        step_result
+    }
+
+    /// Place a flag on a hidden spot, or add one to a flagged spot which can hold more mines, or else remove its flags
+    pub fn flag(&mut self, x: u16, y: u16) -> FlagToggleResult {
+        if !self.can_move() {
+            return FlagToggleResult::None;
+        }
+
+        self.start();
+        self.replay.record(self.stopwatch.millis(), Action::ToggleFlag(x, y));
+        self.clicks += 1;
+        let snapshot = self.snapshot_before_move();
+
+        let flag_result = self.minefield.toggle_flag(x, y);
+        if flag_result != FlagToggleResult::None {
+            self.placed_flags = self.minefield.flags();
+            self.effective_clicks += 1;
+            self.remember_move(snapshot);
+        }
+
+        self.events.push(GameEvent::FlagToggled {
+            x,
+            y,
+            result: flag_result,
+        });
+        self.check_finished(StepResult::Phew);
+
+        flag_result
+    }
+
+    /// Count a click which changed nothing, such as a click on a revealed spot which isn't a chord
+    pub fn waste_click(&mut self) {
+        if self.can_move() {
+            self.clicks += 1;
+        }
+    }
+
+    /// Can the last move be taken back? A lost game can only be taken back in practice mode.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_history.is_empty()
+            && !self.is_paused
+            && match self.state {
+                GameState::Ready | GameState::Running => true,
+                GameState::Stopped(is_won) => !is_won && self.config.play_mode == PlayMode::Practice,
+            }
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_history.is_empty() && !self.is_paused && !matches!(self.state, GameState::Stopped(_))
+    }
+
+    /// Take back the last move. The game can't enter the high scores anymore.
+    pub fn undo(&mut self) {
+        if self.can_undo() {
+            if let Some(snapshot) = self.undo_history.pop() {
+                // A lost game comes back to life, so it's not finished after all
+                if let GameState::Stopped(_) = self.state {
+                    self.events.push(GameEvent::Revived);
+                }
+
+                self.replay.record(self.stopwatch.millis(), Action::Undo);
+                self.redo_history.push(self.snapshot());
+                self.restore_snapshot(snapshot);
+                self.is_practice = true;
+                self.events.push(GameEvent::Undone);
+            }
+        }
+    }
+
+    /// Make again the last move which was taken back
+    pub fn redo(&mut self) {
+        if self.can_redo() {
+            if let Some(snapshot) = self.redo_history.pop() {
+                self.replay.record(self.stopwatch.millis(), Action::Redo);
+                self.undo_history.push(self.snapshot());
+                self.restore_snapshot(snapshot);
+                self.events.push(GameEvent::Redone);
+            }
+        }
+    }
+
+    /// Stop the stopwatch, if the game is running. No moves can be made until the game is unpaused.
+    pub fn pause(&mut self) {
+        if self.state == GameState::Running && !self.is_paused {
+            self.is_paused = true;
+            self.stopwatch.stop();
+            self.events.push(GameEvent::Paused);
+        }
+    }
+
+    /// Start the stopwatch again, if the game is paused
+    pub fn unpause(&mut self) {
+        if self.is_paused {
+            self.is_paused = false;
+            self.stopwatch.start();
+            self.events.push(GameEvent::Unpaused);
+        }
+    }
+
+    /// Ask the solver for one spot which can be deduced from the visible state of the minefield. Every hint given is
+    /// remembered in the high scores.
+    pub fn hint(&mut self) -> Option<((u16, u16), Deduction)> {
+        let hint = solver::deduce(&self.minefield).first().copied();
+        if hint.is_some() {
+            self.hints_used += 1;
+        }
+
+        hint
+    }
+
+    /// Everything that happened in the game since the events were last taken, oldest first
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn minefield(&self) -> &Minefield {
+        &self.minefield
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Time on the stopwatch, in milliseconds. It starts with the first move, and stops when the game is paused or
+    /// over.
+    pub fn millis(&self) -> u64 {
+        self.stopwatch.millis()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Number of flags placed in the minefield
+    pub fn placed_flags(&self) -> u32 {
+        self.placed_flags
+    }
+
+    /// Number of hints given by the solver
+    pub fn hints_used(&self) -> u32 {
+        self.hints_used
+    }
+
+    /// Is this a practice game (or a game with undone moves), which doesn't count for the high scores and statistics?
+    pub fn is_practice(&self) -> bool {
+        self.is_practice
+    }
+
+    /// Number of clicks, including wasted clicks which changed nothing
+    pub fn clicks(&self) -> u32 {
+        self.clicks
+    }
+
+    /// Number of clicks which revealed or flagged spots
+    pub fn effective_clicks(&self) -> u32 {
+        self.effective_clicks
+    }
+
+    /// 3BV of the minefield, known once the mines are placed (see `Minefield::bbbv`)
+    pub fn bbbv(&self) -> u32 {
+        self.bbbv
+    }
+
+    /// Seed of the random number generator which lays the mines
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Spot where the game should be started, if the minefield is the same for every player
+    pub fn start_spot(&self) -> Option<(u16, u16)> {
+        self.start_spot
+    }
+
+    /// Was the minefield meant to be solvable without guessing, but none was found in time? The game is then played on
+    /// a minefield which may need guessing.
+    pub fn may_need_guessing(&self) -> bool {
+        self.may_need_guessing
+    }
+
+    /// Recording of the game so far
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The board of the game, once its mines have been placed. It starts where the game was started, as long as that
+    /// was not on a mine. The board gives away where the mines are, so it is kept secret while a ranked game is
+    /// running.
+    pub fn board(&self) -> Option<Board> {
+        if self.minefield.mines() == 0 || (self.state == GameState::Running && !self.is_practice) {
+            return None;
+        }
+
+        let first_step = self
+            .replay
+            .actions
+            .iter()
+            .find_map(|replay_action| match replay_action.action {
+                Action::Step(x, y) => Some((x, y)),
+                _ => None,
+            });
+
+        let mut board = Board::of(&self.config, &self.minefield, self.start_spot.or(first_step));
+        board.start = board.start.filter(|start| !board.mines.contains(start));
+        Some(board)
+    }
+
+    /// Can the player make a move?
+    fn can_move(&self) -> bool {
+        matches!(self.state, GameState::Ready | GameState::Running) && !self.is_paused
+    }
+
+    /// Start the stopwatch with the first move
+    fn start(&mut self) {
+        if self.state == GameState::Ready {
+            self.state = GameState::Running;
+            self.stopwatch.start();
+            self.events.push(GameEvent::Started);
+        }
+    }
+
+    /// End the game if the last step landed on a mine, or if the minefield was cleared
+    fn check_finished(&mut self, step_result: StepResult) {
+        if step_result == StepResult::Boom {
+            self.finish(false);
+        } else if self.minefield.is_cleared() {
+            self.finish(true);
+        }
+    }
+
+    fn finish(&mut self, is_won: bool) {
+        self.state = GameState::Stopped(is_won);
+        self.stopwatch.stop();
+        self.replay.finish(is_won);
+        self.events.push(GameEvent::Finished { is_won });
+    }
+
+    /// Lay the mines, now that the first step is about to be taken at the given coordinates
+    fn place_mines(&mut self, x: u16, y: u16) {
+        // A seeded game is laid out around its start spot, which is where the first step is taken, so that every
+        // player of the seed gets the same minefield
+        let (mut rng, _) = Self::seeded_rng(&self.config, self.seed);
+        let (x, y) = self.start_spot.unwrap_or((x, y));
+
+        let mut excluded = vec![(x, y)];
+
+        // Solving a minefield without guessing needs more than one revealed spot to start from
+        if self.config.first_click == FirstClick::Opening || self.config.generator == Generator::NoGuess {
+            let neighbors: Vec<(u16, u16)> = self.minefield.neighbors_coords(x, y).collect();
+
+            // Very crowded minefields may not leave enough room for an opening, in which case the first step is
+            // merely safe
+            let room = (self.minefield.spots().count() - excluded.len() - neighbors.len())
+                * self.minefield.mines_per_spot() as usize;
+            if (self.config.mines as usize) <= room {
+                excluded.extend(neighbors);
+            }
+        }
+
+        // Flags may have been placed before the first step, and they must survive the new minefield
+        let flags: Vec<(u16, u16)> = self
+            .minefield
+            .spots()
+            .flat_map(|(coords, spot)| std::iter::repeat_n(*coords, spot.flags as usize))
+            .collect();
+
+        let mut new_minefield = || {
+            self.config
+                .empty_minefield()
+                .with_mines_excluding_using(self.config.mines, &excluded, &mut rng)
+        };
+
+        self.minefield = match (&self.board, self.config.generator) {
+            // A board which was passed on is already laid out
+            (Some(board), _) => board.minefield(),
+            (None, Generator::Random) => new_minefield(),
+            (None, Generator::NoGuess) => {
+                let mut stopwatch = Stopwatch::default();
+                stopwatch.start();
+
+                let spots = self.minefield.spots().count();
+                let max_attempts =
+                    (self.config.seed != Seed::Random).then(|| (Self::NO_GUESS_SEEDED_SPOTS / spots).max(1));
+                let mut attempts = 0;
+
+                // At least one minefield is always tried, however slow the clock
+                let (minefield, is_solvable) = loop {
+                    let minefield = new_minefield();
+                    let is_solvable = solver::is_solvable_from(&minefield, x, y);
+                    attempts += 1;
+
+                    let is_given_up = match max_attempts {
+                        Some(max_attempts) => attempts >= max_attempts,
+                        None => stopwatch.millis() >= Self::NO_GUESS_MILLIS,
+                    };
+                    if is_solvable || is_given_up {
+                        break (minefield, is_solvable);
+                    }
+                };
+
+                if !is_solvable {
+                    tracing::warn!(
+                        "No minefield solvable without guessing found in time for {:?}",
+                        self.config
+                    );
+                    self.may_need_guessing = true;
+                }
+
+                minefield
+            }
+        };
+
+        for (fx, fy) in flags {
+            self.minefield.toggle_flag(fx, fy);
+        }
+
+        self.replay.mines = self.minefield.mines_coords().collect();
+        self.bbbv = self.minefield.bbbv();
+    }
+
+    /// Random number generator which lays the mines of a game with the given seed, and the spot where the game
+    /// starts if it is seeded
+    fn seeded_rng(config: &GameConfig, seed: u64) -> (StdRng, (u16, u16)) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start_spot = (rng.gen_range(0..config.width), rng.gen_range(0..config.height));
+        (rng, start_spot)
+    }
+
+    /// The spot where a game with the given seed starts, unless the seed is random
+    fn seeded_start_spot(config: &GameConfig, seed: u64) -> Option<(u16, u16)> {
+        (config.seed != Seed::Random).then(|| Self::seeded_rng(config, seed).1)
+    }
+
+    /// The current state of the game, right before the player's move, unless there is nothing to go back to because
+    /// the mines are not placed yet
+    fn snapshot_before_move(&self) -> Option<Snapshot> {
+        (self.minefield.mines() > 0).then(|| self.snapshot())
+    }
+
+    /// Make a move which changed the minefield undoable, given the snapshot taken before it. Moves which changed
+    /// nothing are never remembered, so they neither need undoing nor throw away the moves which can be redone.
+    fn remember_move(&mut self, snapshot: Option<Snapshot>) {
+        self.undo_history.extend(snapshot);
+        self.redo_history.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            minefield: self.minefield.clone(),
+            placed_flags: self.placed_flags,
+            game_state: self.state,
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.minefield = snapshot.minefield;
+        self.placed_flags = snapshot.placed_flags;
+
+        match (self.state, snapshot.game_state) {
+            (GameState::Stopped(_), GameState::Running) => self.stopwatch.start(),
+            (GameState::Running, GameState::Stopped(_)) => self.stopwatch.stop(),
+            (_, _) => {}
+        }
+        self.state = snapshot.game_state;
+    }
+}
+
+/// Something which happened in a game
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GameEvent {
+    /// The first move was made, and the stopwatch started
+    Started,
+
+    /// A spot was stepped on
+    Revealed {
+        x: u16,
+        y: u16,
+        result: StepResult,
+    },
+
+    /// The neighbors of a revealed spot were stepped on
+    Chorded {
+        x: u16,
+        y: u16,
+        result: StepResult,
+    },
+
+    /// A flag was placed or removed
+    FlagToggled {
+        x: u16,
+        y: u16,
+        result: FlagToggleResult,
+    },
+
+    /// The last move was taken back
+    Undone,
+
+    /// The last move which was taken back was made again
+    Redone,
+
+    Paused,
+    Unpaused,
+
+    /// The game is over, and was either won (`true`), or lost (`false`)
+    Finished {
+        is_won: bool,
+    },
+
+    /// A lost game came back to life, because its last move was taken back
+    Revived,
+}
+
+/// Current state of the game
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum GameState {
+    /// Game is ready to start running
+    #[default]
+    Ready,
+
+    /// Game is running
+    Running,
+
+    /// Game is stopped, and was either won (`true`), or lost (`false`)
+    Stopped(bool),
+}
+
+/// Everything needed to continue a game after the app is restarted
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    minefield: Minefield,
+    placed_flags: u32,
+    game_state: GameState,
+    hints_used: u32,
+    is_practice: bool,
+    clicks: u32,
+    effective_clicks: u32,
+    replay: Replay,
+    millis: u64,
+    seed: u64,
+    /// Code of the board the game is played on, if it was passed on
+    board: Option<String>,
+    may_need_guessing: bool,
+}
+
+/// State of the game before a move, so that the move can be undone
+struct Snapshot {
+    minefield: Minefield,
+    placed_flags: u32,
+    game_state: GameState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub width: u16,
+    pub height: u16,
+    pub mines: u32,
+    #[serde(default)]
+    pub first_click: FirstClick,
+    #[serde(default)]
+    pub generator: Generator,
+    #[serde(default)]
+    pub play_mode: PlayMode,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default = "GameConfig::default_mines_per_spot")]
+    pub mines_per_spot: u8,
+    #[serde(default)]
+    pub seed: Seed,
+}
+
+impl GameConfig {
+    pub const MIN_WIDTH: u16 = 5;
+    pub const MAX_WIDTH: u16 = 60;
+    pub const MIN_HEIGHT: u16 = 5;
+    pub const MAX_HEIGHT: u16 = 40;
+    pub const MIN_MINES: u32 = 1;
+    pub const MAX_MINES_PER_SPOT: u8 = 3;
+
+    fn default_mines_per_spot() -> u8 {
+        1
+    }
+
+    /// Maximum number of mines which still leaves at least one empty spot in the minefield
+    pub fn max_mines(&self) -> u32 {
+        (self.width as u32 * self.height as u32).saturating_sub(1) * self.mines_per_spot as u32
+    }
+
+    /// This config, with the minefield dimensions and number of mines of `other`
+    pub fn with_board_of(self, other: &GameConfig) -> Self {
+        Self {
+            width: other.width,
+            height: other.height,
+            mines: other.mines,
+            ..self
+        }
+    }
+
+    /// Check if this config has the same minefield dimensions and number of mines as `other`
+    pub fn has_board_of(&self, other: &GameConfig) -> bool {
+        self.width == other.width && self.height == other.height && self.mines == other.mines
+    }
+
+    /// This config, as played in a ranked game
+    pub fn ranked(self) -> Self {
+        Self {
+            play_mode: PlayMode::Ranked,
+            ..self
+        }
+    }
+
+    /// A minefield laid out as described by this config, without any mines yet
+    pub fn empty_minefield(&self) -> Minefield {
+        Minefield::new(self.width, self.height)
+            .with_topology(self.topology)
+            .with_mines_per_spot(self.mines_per_spot)
+    }
+
+    /// Check if this config describes a playable minefield
+    pub fn is_valid(&self) -> bool {
+        (Self::MIN_WIDTH..=Self::MAX_WIDTH).contains(&self.width)
+            && (Self::MIN_HEIGHT..=Self::MAX_HEIGHT).contains(&self.height)
+            && (1..=Self::MAX_MINES_PER_SPOT).contains(&self.mines_per_spot)
+            && (Self::MIN_MINES..=self.max_mines()).contains(&self.mines)
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameDifficulty::EASY
+    }
+}
+
+/// What is guaranteed about the first step in a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FirstClick {
+    /// The first step never lands on a mine
+    #[default]
+    Safe,
+
+    /// The first step always lands on a spot with no neighboring mines, so it opens up an area of the minefield
+    Opening,
+}
+
+/// How the mines are laid out in the minefield
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Generator {
+    /// Mines are placed at random
+    #[default]
+    Random,
+
+    /// Mines are placed so that the minefield can be cleared without guessing, starting from the first step
+    NoGuess,
+}
+
+/// Where the seed of the random number generator which lays the mines comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Seed {
+    /// Every game has a new random seed
+    #[default]
+    Random,
+
+    /// Every game has the given seed, and so the same minefield
+    Fixed(u64),
+
+    /// The seed is the date (in UTC), so that everyone plays the same minefield on the same day
+    Daily,
+}
+
+impl Seed {
+    /// The seed of a new game
+    pub fn new_game_seed(&self) -> u64 {
+        match self {
+            // Short enough to be shared
+            Seed::Random => rand::thread_rng().gen::<u32>() as u64,
+            Seed::Fixed(seed) => *seed,
+            Seed::Daily => Self::today(),
+        }
+    }
+
+    /// The daily seed of the current day, which is the date written as a `YYYYMMDD` number
+    pub fn today() -> u64 {
+        chrono::Utc::now()
+            .format("%Y%m%d")
+            .to_string()
+            .parse()
+            .expect("Dates are numbers")
+    }
+
+    /// The date of the given daily seed, formatted for display
+    pub fn date_of(daily_seed: u64) -> String {
+        format!(
+            "{}-{:02}-{:02}",
+            daily_seed / 10000,
+            daily_seed / 100 % 100,
+            daily_seed % 100
+        )
+    }
+}
+
+/// Whether a game counts for the high scores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlayMode {
+    /// Won games enter the high scores, and no analysis of the minefield is available
+    #[default]
+    Ranked,
+
+    /// Won games never enter the high scores, and the minefield can be analyzed freely
+    Practice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameDifficulty {
+    Easy,
+    Medium,
+    Hard,
+    Custom,
+}
+
+impl GameDifficulty {
+    pub const EASY: GameConfig = GameConfig {
+        width: 10,
+        height: 10,
+        mines: 10,
+        first_click: FirstClick::Safe,
+        generator: Generator::Random,
+        play_mode: PlayMode::Ranked,
+        topology: Topology::Square,
+        mines_per_spot: 1,
+        seed: Seed::Random,
+    };
+    pub const MEDIUM: GameConfig = GameConfig {
+        width: 16,
+        height: 16,
+        mines: 40,
+        first_click: FirstClick::Safe,
+        generator: Generator::Random,
+        play_mode: PlayMode::Ranked,
+        topology: Topology::Square,
+        mines_per_spot: 1,
+        seed: Seed::Random,
+    };
+    pub const HARD: GameConfig = GameConfig {
+        width: 30,
+        height: 16,
+        mines: 99,
+        first_click: FirstClick::Safe,
+        generator: Generator::Random,
+        play_mode: PlayMode::Ranked,
+        topology: Topology::Square,
+        mines_per_spot: 1,
+        seed: Seed::Random,
+    };
+
+    pub fn from_config(config: &GameConfig) -> Self {
+        if config.has_board_of(&Self::EASY) {
+            Self::Easy
+        } else if config.has_board_of(&Self::MEDIUM) {
+            Self::Medium
+        } else if config.has_board_of(&Self::HARD) {
+            Self::Hard
+        } else {
+            Self::Custom
+        }
+    }
+}
+
+/// Native stopwatch, which measures how long a game has been running for
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Stopwatch {
+    /// When the stopwatch was last started, if it is running
+    started_at: Option<std::time::Instant>,
+    /// Milliseconds measured before the stopwatch was last started
+    millis_before: u64,
+}
+
+/// WASM stopwatch, which measures how long a game has been running for
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+struct Stopwatch {
+    /// Value of `performance.now()` when the stopwatch was last started, if it is running
+    started_at: Option<f64>,
+    /// Milliseconds measured before the stopwatch was last started
+    millis_before: u64,
+}
+
+impl Stopwatch {
+    pub fn stop(&mut self) {
+        self.millis_before = self.millis();
+        self.started_at = None;
+    }
+
+    pub fn start(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.started_at = Some(std::time::Instant::now());
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.started_at = Some(Self::performance_now());
+        }
+    }
+
+    /// Milliseconds the stopwatch has been running for, not counting the time it was stopped
+    pub fn millis(&self) -> u64 {
+        #[cfg(not(target_arch = "wasm32"))]
+        let running = self
+            .started_at
+            .map(|started_at| started_at.elapsed().as_millis() as u64);
+
+        #[cfg(target_arch = "wasm32")]
+        let running = self
+            .started_at
+            .map(|started_at| (Self::performance_now() - started_at).max(0.0) as u64);
+
+        self.millis_before + running.unwrap_or(0)
+    }
+
+    /// Milliseconds since the page was loaded, from a clock which never goes back
+    #[cfg(target_arch = "wasm32")]
+    fn performance_now() -> f64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map(|performance| performance.now())
+            .unwrap_or(0.0)
+    }
+}
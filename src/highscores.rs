@@ -0,0 +1,153 @@
+use crate::game::GameConfig;
+use serde::{Deserialize, Serialize};
+
+/// Leaderboard of won games, with one table for each game configuration that was ever won
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    tables: Vec<HighScoreTable>,
+}
+
+impl HighScores {
+    /// Maximum number of entries kept in each table
+    pub const MAX_ENTRIES: usize = 10;
+
+    /// Record a won game. Returns the rank (starting from `0`) of the new entry, or `None` if the score was not good
+    /// enough to make it into the table.
+    pub fn add(&mut self, config: &GameConfig, score: HighScore) -> Option<usize> {
+        let table = if let Some(pos) = self.tables.iter().position(|t| t.config == *config) {
+            &mut self.tables[pos]
+        } else {
+            self.tables.push(HighScoreTable {
+                config: *config,
+                scores: vec![],
+            });
+            self.tables.last_mut().unwrap()
+        };
+
+        // Entries with equal times keep their original order, so an older score stays ahead of a newer one
+        let rank = table.scores.partition_point(|s| s.millis <= score.millis);
+
+        if rank < Self::MAX_ENTRIES {
+            table.scores.insert(rank, score);
+            table.scores.truncate(Self::MAX_ENTRIES);
+            Some(rank)
+        } else {
+            None
+        }
+    }
+
+    /// The scores recorded for the given game configuration, best first
+    pub fn scores(&self, config: &GameConfig) -> &[HighScore] {
+        self.tables
+            .iter()
+            .find(|t| t.config == *config)
+            .map(|t| t.scores.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Iterator over all the game configurations which have at least one recorded score
+    pub fn configs(&self) -> impl Iterator<Item = &GameConfig> {
+        self.tables.iter().filter(|t| !t.scores.is_empty()).map(|t| &t.config)
+    }
+
+    /// Remove all the scores recorded for the given game configuration
+    pub fn clear(&mut self, config: &GameConfig) {
+        self.tables.retain(|t| t.config != *config);
+    }
+
+    /// Remove all recorded scores
+    pub fn clear_all(&mut self) {
+        self.tables.clear();
+    }
+}
+
+/// Leaderboard of the daily challenge. Only the scores of the most recent day are kept, since everyone plays a new
+/// minefield every day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyHighScores {
+    /// The day the scores were recorded on, as the seed of that day's minefields
+    day: u64,
+    high_scores: HighScores,
+}
+
+impl DailyHighScores {
+    /// Record a won daily game, played on the minefield of the given `day`. The scores of earlier days are forgotten.
+    /// Returns the rank (starting from `0`) of the new entry, or `None` if the score was not good enough to make it
+    /// into the table, or if it is from an earlier day.
+    pub fn add(&mut self, day: u64, config: &GameConfig, score: HighScore) -> Option<usize> {
+        if day < self.day {
+            return None;
+        }
+
+        if day > self.day {
+            self.day = day;
+            self.high_scores.clear_all();
+        }
+
+        self.high_scores.add(config, score)
+    }
+
+    /// The scores recorded on the given day for the given game configuration, best first
+    pub fn scores(&self, day: u64, config: &GameConfig) -> &[HighScore] {
+        if day == self.day {
+            self.high_scores.scores(config)
+        } else {
+            &[]
+        }
+    }
+
+    /// Iterator over all the game configurations which have at least one score recorded on the given day
+    pub fn configs(&self, day: u64) -> impl Iterator<Item = &GameConfig> {
+        self.high_scores.configs().filter(move |_| day == self.day)
+    }
+
+    /// Remove all the scores recorded for the given game configuration
+    pub fn clear(&mut self, config: &GameConfig) {
+        self.high_scores.clear(config);
+    }
+
+    /// Remove all recorded scores
+    pub fn clear_all(&mut self) {
+        self.high_scores.clear_all();
+    }
+}
+
+/// All the scores recorded for a particular game configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HighScoreTable {
+    config: GameConfig,
+    scores: Vec<HighScore>,
+}
+
+/// One entry in the leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    /// Name of the player who won the game
+    pub name: String,
+
+    /// Time it took to clear the minefield, in milliseconds
+    pub millis: u64,
+
+    /// Local date and time when the game was won, formatted for display
+    pub date: String,
+
+    /// Number of hints the player asked for while playing. Games with hints are assisted games.
+    pub hints: u32,
+}
+
+impl HighScore {
+    /// Create a new entry, dated now
+    pub fn new(name: &str, millis: u64, hints: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            millis,
+            date: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            hints,
+        }
+    }
+
+    /// Was the game won with help from the solver?
+    pub fn is_assisted(&self) -> bool {
+        self.hints > 0
+    }
+}
@@ -1 +1,13 @@
-pub mod app;
\ No newline at end of file
+pub mod app;
+pub mod board;
+pub mod cli;
+pub mod game;
+pub mod highscores;
+pub mod minefield;
+pub mod solver;
+pub mod probability;
+pub mod records;
+pub mod replay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod settings_file;
+pub mod statistics;
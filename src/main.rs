@@ -1,10 +1,19 @@
-pub mod app;
-
 use std::env;
-use eframe::{NativeOptions, epaint::Vec2};
-use app::{GameDifficulty, MinesweepRsApp};
+use eframe::NativeOptions;
+use minesweep_rs::app::MinesweepRsApp;
+use minesweep_rs::cli::{Args, Theme};
 
 fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => exit_with_error(&e),
+    };
+
+    if args.help {
+        println!("{}", Args::USAGE);
+        return;
+    }
+
     // DEBUG 
     env::set_var("RUST_BACKTRACE", "full");
     
@@ -14,32 +23,38 @@ fn main() {
         .with_line_number(true)
         .init();    
     
-    // FIXME: Solve auto resizing
-    let size_x = 38.0;
-    let size_y = 44.0;
-
+    // The app resizes the window to fit the minefield, once it knows which one is played
     let options = NativeOptions {
-        initial_window_size: Some(
-            // Allocate the maximum native window size, corresponding to the HARD game difficulty
-            Vec2::new(
-                size_x * GameDifficulty::HARD.width as f32,
-                size_y * GameDifficulty::HARD.height as f32
-            )
-        ),
-        resizable: false,
         // FIXME: App crashes (on Fedora, with Wayland) when run with `options.run_and_return = true;` and in a `loop`
         run_and_return: true,
-        follow_system_theme: false,
-        default_theme: eframe::Theme::Dark,
+        fullscreen: args.fullscreen,
+        follow_system_theme: args.theme == Some(Theme::System),
+        default_theme: match args.theme {
+            Some(Theme::Light) => eframe::Theme::Light,
+            Some(Theme::Dark) | Some(Theme::System) | None => eframe::Theme::Dark,
+        },
         ..Default::default()
     };
 
     eframe::run_native(
-        "Egui Minesweep-Rs",
+        MinesweepRsApp::TITLE,
         options,
-        Box::new(|cc| Box::new(MinesweepRsApp::default().with_context(cc))),
-    );
+        Box::new(move |cc| {
+            let app = MinesweepRsApp::default().with_context(cc);
 
-    // TODO: figure out if we can read App `storage` in order to figure out if we should exit or apply new configs
+            // Arguments which change the config start a new game with it, instead of continuing the saved game. They
+            // can only be checked against the saved config once it is loaded from storage.
+            Box::new(match args.game_config(app.game_config()) {
+                Ok(Some(game_config)) => app.with_configs(game_config),
+                Ok(None) => app,
+                Err(e) => exit_with_error(&e),
+            })
+        }),
+    );
+}
 
+/// Report invalid arguments, the way argument parsers do
+fn exit_with_error(e: &str) -> ! {
+    eprintln!("error: {}\n\nTry 'minesweep-rs --help' for more information.", e);
+    std::process::exit(2);
 }
@@ -0,0 +1,678 @@
+//! The minefield and the rules for stepping on and flagging its spots.
+//!
+//! This module started out as a copy of the [minefield-rs](https://github.com/BogdanOlar/minefield-rs) crate (0.1.5,
+//! MIT), by the same author. It has since grown topologies, multi-mine spots, deferred mine placement and
+//! serialization, so it is now owned and maintained here rather than upstream.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// The characteristics of the minefield
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Minefield {
+    /// The mine field as a set of coords `(x, y)` associated with a `Spot`
+    field: HashMap<(u16, u16), Spot>,
+
+    /// Number of mines in the field
+    mines: u32,
+
+    /// Width of field grid
+    width: u16,
+
+    /// Height of field grid
+    height: u16,
+
+    /// How the spots are laid out, which decides the neighbors of each spot
+    topology: Topology,
+
+    /// Maximum number of mines in one spot
+    mines_per_spot: u8,
+}
+
+impl Minefield {
+    /// Create an empty minefield grid (with all spots hidden), with the given width and height
+    pub fn new(width: u16, height: u16) -> Self {
+        // Enforce a minimum number of spots
+        let width = if width == 0 { 1 } else { width };
+        let height = if height == 0 { 1 } else { height };
+
+        let field: HashMap<(u16, u16), Spot> = (0..width)
+            .flat_map(move |i| (0..height).map(move |j| (i, j)))
+            .map(|(x, y)| ((x, y), Spot::default()))
+            .collect();
+
+        // Create empty Minefield
+        Minefield {
+            field,
+            mines: 0,
+            width,
+            height,
+            topology: Topology::default(),
+            mines_per_spot: 1,
+        }
+    }
+
+    /// Build an existing minefield where each spot can hold up to `mines_per_spot` mines. A spot's number is then the
+    /// total of the mines in its neighbors, and it takes as many flags as it has mines to mark it. Must be called
+    /// before placing mines.
+    pub fn with_mines_per_spot(mut self, mines_per_spot: u8) -> Self {
+        assert_eq!(
+            self.mines, 0,
+            "The number of mines per spot must be set before placing mines"
+        );
+        self.mines_per_spot = mines_per_spot.max(1);
+        self
+    }
+
+    /// Build an existing minefield with the given topology. Mines must be placed after the topology is set, so that
+    /// the neighboring mines are counted correctly.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        assert_eq!(self.mines, 0, "The topology must be set before placing mines");
+        self.topology = topology;
+        self
+    }
+
+    /// Build an existing minefield with the given number of mines randomly placed in it
+    pub fn with_mines(self, mines: u32) -> Self {
+        self.with_mines_excluding(mines, &[])
+    }
+
+    /// Build an existing minefield with the given number of mines randomly placed in it, but never on the `excluded`
+    /// spots
+    pub fn with_mines_excluding(self, mines: u32, excluded: &[(u16, u16)]) -> Self {
+        self.with_mines_excluding_using(mines, excluded, &mut rand::thread_rng())
+    }
+
+    /// Build an existing minefield with the given number of mines placed in it by the given random number generator,
+    /// but never on the `excluded` spots. A seeded generator always places the mines in the same spots.
+    pub fn with_mines_excluding_using(mut self, mines: u32, excluded: &[(u16, u16)], rng: &mut impl Rng) -> Self {
+        // Total number of spots in our field
+        let spot_count = self.width as usize * self.height as usize;
+
+        // Add mines to minefield
+
+        // We could just start randomly picking indices in the field and hope we haven't picked them before, but if a
+        // user desires a field full of mines, then waiting for the last mines to be placed might take a long time
+        // (e.g. if the field is very large).
+        // That's a problem for an immediate GUI.
+        // So, instead, we'll use some memory in order to ensure that the user can step on a mine as soon as humanly
+        // possible.
+        // Each spot is available once for each mine it can hold
+        let width = self.width as u32;
+        let mut spots_remaining: Vec<usize> = (0..spot_count)
+            .filter(|index| {
+                let x = (*index as u32 % width) as u16;
+                let y = (*index as u32 / width) as u16;
+                !excluded.contains(&(x, y))
+            })
+            .flat_map(|index| std::iter::repeat_n(index, self.mines_per_spot as usize))
+            .collect();
+
+        // Limit the max number of mines to the number of available spots
+        let mines = if mines as usize <= spots_remaining.len() {
+            mines
+        } else {
+            spots_remaining.len() as u32
+        };
+
+        self.mines = mines;
+
+        // Place mines
+        for _ in 0..self.mines {
+            let index_rm = rng.gen_range(0..spots_remaining.len());
+            let index = spots_remaining.swap_remove(index_rm);
+            let x = (index as u32 % width) as u16;
+            let y = (index as u32 / width) as u16;
+            self.place_mine(x, y);
+        }
+
+        self
+    }
+
+    /// Build an existing minefield with mines placed at the given coordinates. Coordinates which are repeated place
+    /// more than one mine in the same spot, if the spot can hold them.
+    pub fn with_mines_at(mut self, mines: &[(u16, u16)]) -> Self {
+        for (x, y) in mines {
+            if *x < self.width && *y < self.height && self.place_mine(*x, *y) {
+                self.mines += 1;
+            }
+        }
+
+        self
+    }
+
+    /// Step on a given spot of the field. Coordinates [x=0, y=0] represent the top-left point of the field grid
+    pub fn step(&mut self, x: u16, y: u16) -> StepResult {
+        if let Some(spot) = self.field.get_mut(&(x, y)) {
+            let step_result = spot.step();
+
+            // flood reveal, if this is an empty spot with no neighboring mines
+            if let SpotState::RevealedEmpty { neighboring_mines: 0 } = spot.state {
+                let mut spots_to_visit = vec![(x, y)];
+
+                while let Some((xx, yy)) = spots_to_visit.pop() {
+                    for n_coords in self.neighbors_coords(xx, yy) {
+                        let spot = self.field.get_mut(&n_coords).unwrap();
+
+                        if let SpotState::HiddenEmpty { neighboring_mines } = spot.state {
+                            // Reveal the hidden empty spot by stepping on it
+                            let step_result = spot.step();
+                            debug_assert_eq!(step_result, StepResult::Phew);
+
+                            if neighboring_mines == 0 {
+                                // continue flood revealing neighbors from this spot
+                                spots_to_visit.push(n_coords);
+                            }
+                        }
+                    }
+                }
+            }
+
+            step_result
+        } else {
+            // Step is outside minefield
+            StepResult::Invalid
+        }
+    }
+
+    /// Automatically step on all hidden neighbors (i.e. not flagged) of a revealed spot at the given coordinates
+    pub fn auto_step(&mut self, x: u16, y: u16) -> StepResult {
+        if let Some(spot) = self.field.get(&(x, y)) {
+            if let SpotState::RevealedEmpty { neighboring_mines } = spot.state {
+                // count the flags around the given coords
+                let placed_flags: u8 = self
+                    .neighbors_coords(x, y)
+                    .map(|(x, y)| self.field.get(&(x, y)).unwrap().flags)
+                    .sum();
+
+                // Only try to autostep if the user has placed enough flags around the spot whose neighbors will be
+                // autorevealed
+                if placed_flags == neighboring_mines {
+                    for (nx, ny) in self.neighbors_coords(x, y) {
+                        if StepResult::Boom == self.step(nx, ny) {
+                            // Eager Boom return
+                            return StepResult::Boom;
+                        }
+                    }
+
+                    StepResult::Phew
+                } else {
+                    // Not enough flags placed by user in order to autostep
+                    StepResult::Invalid
+                }
+            } else {
+                // Spot is not revealed yet
+                StepResult::Invalid
+            }
+        } else {
+            // invalid spot coordinates
+            StepResult::Invalid
+        }
+    }
+
+    /// Check if the minefield has been cleared
+    pub fn is_cleared(&self) -> bool {
+        for (_spot_coords, spot) in self.spots() {
+            if !spot.is_resolved() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Set a flag on a hidden spot, or add one more flag to a flagged spot if it can hold more mines, or else clear its
+    /// flags, or do nothing if the spot cannot be flagged
+    pub fn toggle_flag(&mut self, x: u16, y: u16) -> FlagToggleResult {
+        let mines_per_spot = self.mines_per_spot;
+        if let Some(spot) = self.field.get_mut(&(x, y)) {
+            spot.flag(mines_per_spot)
+        } else {
+            // invalid coordinates, no flag was added or removed
+            FlagToggleResult::None
+        }
+    }
+
+    /// The width of the minefield
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// The height of the minefield
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The layout of the spots in the minefield
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The number of mines in the minefield
+    pub fn mines(&self) -> u32 {
+        self.mines
+    }
+
+    /// The maximum number of mines in one spot
+    pub fn mines_per_spot(&self) -> u8 {
+        self.mines_per_spot
+    }
+
+    /// The number of flags placed in the minefield
+    pub fn flags(&self) -> u32 {
+        self.field.values().map(|spot| spot.flags as u32).sum()
+    }
+
+    /// Get a reference to a particular `Spot` in the field
+    pub fn spot(&self, x: u16, y: u16) -> Option<&Spot> {
+        self.field.get(&(x, y))
+    }
+
+    /// Iterator for all `Spot`s in the field, together with their coordinates `(x, y)`
+    pub fn spots(&self) -> impl Iterator<Item = (&(u16, u16), &Spot)> {
+        self.field.iter()
+    }
+
+    /// Iterator over the coordinates of all the mines in the field, whether they are hidden, flagged or exploded. A
+    /// spot holding more than one mine is repeated once for each of its mines.
+    pub fn mines_coords(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.field
+            .iter()
+            .flat_map(|(coords, spot)| std::iter::repeat_n(*coords, spot.mines as usize))
+    }
+
+    /// The minimum number of clicks needed to clear the minefield without flags or chording (its "3BV"): one click for
+    /// each opening (an area of spots with no neighboring mines, which is revealed all at once, together with its
+    /// border), plus one click for each empty spot which doesn't border an opening
+    pub fn bbbv(&self) -> u32 {
+        self.bbbv_counts().0
+    }
+
+    /// The part of the 3BV (see `bbbv`) which has been cleared so far: the revealed openings, and the revealed empty
+    /// spots which don't border an opening
+    pub fn solved_bbbv(&self) -> u32 {
+        self.bbbv_counts().1
+    }
+
+    /// The total and the solved 3BV
+    fn bbbv_counts(&self) -> (u32, u32) {
+        let mut bbbv = 0;
+        let mut solved = 0;
+        let mut is_counted: HashSet<(u16, u16)> = HashSet::new();
+        let is_revealed = |x: u16, y: u16| {
+            matches!(
+                self.field.get(&(x, y)).map(|spot| spot.state),
+                Some(SpotState::RevealedEmpty { neighboring_mines: _ })
+            )
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.neighboring_mines(x, y) != Some(0) || is_counted.contains(&(x, y)) {
+                    continue;
+                }
+
+                // A new opening, which is revealed all at once
+                bbbv += 1;
+                if is_revealed(x, y) {
+                    solved += 1;
+                }
+                is_counted.insert((x, y));
+                let mut to_visit = vec![(x, y)];
+
+                while let Some((ox, oy)) = to_visit.pop() {
+                    for (nx, ny) in self.neighbors_coords(ox, oy) {
+                        if is_counted.insert((nx, ny)) && self.neighboring_mines(nx, ny) == Some(0) {
+                            to_visit.push((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (x, y) in self.field.keys() {
+            if !is_counted.contains(&(*x, *y)) && self.neighboring_mines(*x, *y).is_some() {
+                bbbv += 1;
+                if is_revealed(*x, *y) {
+                    solved += 1;
+                }
+            }
+        }
+
+        (bbbv, solved)
+    }
+
+    /// Number of mines around the spot at the given coordinates, or `None` if the spot holds a mine itself
+    fn neighboring_mines(&self, x: u16, y: u16) -> Option<u8> {
+        match self.field.get(&(x, y)).map(|spot| spot.state) {
+            Some(
+                SpotState::HiddenEmpty { neighboring_mines }
+                | SpotState::FlaggedEmpty { neighboring_mines }
+                | SpotState::RevealedEmpty { neighboring_mines },
+            ) => Some(neighboring_mines),
+            _ => None,
+        }
+    }
+
+    /// Place a mine at the given field coordinates, and update neighboring spots. Returns `false` if the spot can't
+    /// hold any more mines.
+    fn place_mine(&mut self, x: u16, y: u16) -> bool {
+        assert!(x < self.width);
+        assert!(y < self.height);
+
+        if let Some(spot) = self.field.get_mut(&(x, y)) {
+            if spot.mines >= self.mines_per_spot {
+                return false;
+            }
+
+            spot.mines += 1;
+
+            match spot.state {
+                // An empty spot becomes a mine, and a mine only gets one more
+                SpotState::HiddenEmpty { neighboring_mines: _ }
+                | SpotState::FlaggedEmpty { neighboring_mines: _ }
+                | SpotState::RevealedEmpty { neighboring_mines: _ } => {
+                    spot.state = SpotState::HiddenMine;
+                    spot.flags = 0;
+                }
+                _ => {}
+            }
+
+            // Update counts of empty neighboring spots
+            for (nx, ny) in self.neighbors_coords(x, y) {
+                if let Some(spot) = self.field.get_mut(&(nx, ny)) {
+                    match &mut spot.state {
+                        // Only empty spots count their neighboring mines
+                        SpotState::HiddenEmpty { neighboring_mines }
+                        | SpotState::FlaggedEmpty { neighboring_mines }
+                        | SpotState::RevealedEmpty { neighboring_mines } => {
+                            *neighboring_mines += 1;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check if the spot at the given coordinates has any hidden neighbor which isn't flagged, which chording on it
+    /// would step on
+    pub fn has_hidden_neighbors(&self, x: u16, y: u16) -> bool {
+        self.neighbors_coords(x, y).any(|(nx, ny)| {
+            matches!(
+                self.field.get(&(nx, ny)).unwrap().state,
+                SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine
+            )
+        })
+    }
+
+    /// Iterator over the coordinates of all neighbors of the spot at the given coordinates, as decided by the
+    /// topology of the minefield
+    pub fn neighbors_coords(&self, x: u16, y: u16) -> impl Iterator<Item = (u16, u16)> {
+        let offsets: &[(i32, i32)] = match self.topology {
+            Topology::Square | Topology::Torus => &Topology::SQUARE_OFFSETS,
+            Topology::Hex if y % 2 == 1 => &Topology::HEX_ODD_ROW_OFFSETS,
+            Topology::Hex => &Topology::HEX_EVEN_ROW_OFFSETS,
+        };
+
+        let width = self.width as i32;
+        let height = self.height as i32;
+        let wraps = self.topology == Topology::Torus;
+
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .map(move |(neighbor_x, neighbor_y)| {
+                // the edges of a torus are stitched to the opposite edges
+                if wraps {
+                    (neighbor_x.rem_euclid(width), neighbor_y.rem_euclid(height))
+                } else {
+                    (neighbor_x, neighbor_y)
+                }
+            })
+            .filter(move |(neighbor_x, neighbor_y)| {
+                // the neighbor coords are within the minefield grid
+                (0..width).contains(neighbor_x) && (0..height).contains(neighbor_y)
+            })
+            .map(|(neighbor_x, neighbor_y)| (neighbor_x as u16, neighbor_y as u16))
+    }
+
+    /// Coordinates of the spot next to the given one in the given heading, such as where a cursor moves to. At the
+    /// edges of the minefield this is the given spot itself, except on a torus, which goes around like everything else.
+    pub fn next_coords(&self, x: u16, y: u16, heading: Heading) -> (u16, u16) {
+        let (width, height) = (self.width, self.height);
+
+        match (heading, self.topology == Topology::Torus) {
+            (Heading::Up, true) => (x, (y + height - 1) % height),
+            (Heading::Down, true) => (x, (y + 1) % height),
+            (Heading::Left, true) => ((x + width - 1) % width, y),
+            (Heading::Right, true) => ((x + 1) % width, y),
+            (Heading::Up, false) => (x, y.saturating_sub(1)),
+            (Heading::Down, false) => (x, (y + 1).min(height - 1)),
+            (Heading::Left, false) => (x.saturating_sub(1), y),
+            (Heading::Right, false) => ((x + 1).min(width - 1), y),
+        }
+    }
+}
+
+/// A way across the minefield, as it is laid out on screen
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Heading {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// How the spots of a minefield are laid out
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Topology {
+    /// A grid of squares, where each spot has 8 neighbors
+    #[default]
+    Square,
+
+    /// A grid of hexagons, where each spot has 6 neighbors. Odd rows are shifted right by half a spot.
+    Hex,
+
+    /// A grid of squares whose edges wrap around to the opposite edges, so that every spot has 8 neighbors. The
+    /// minefield must be at least 3 spots wide and high, so that no spot is its own neighbor.
+    Torus,
+}
+
+impl Topology {
+    /// Offsets `(dx, dy)` of the neighbors of a square
+    const SQUARE_OFFSETS: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+    /// Offsets `(dx, dy)` of the neighbors of a hexagon on an even row
+    const HEX_EVEN_ROW_OFFSETS: [(i32, i32); 6] = [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+
+    /// Offsets `(dx, dy)` of the neighbors of a hexagon on an odd row, which is shifted right by half a spot
+    const HEX_ODD_ROW_OFFSETS: [(i32, i32); 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+}
+
+/// State of the spot in a minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SpotState {
+    /// This spot has not been visited
+    HiddenEmpty { neighboring_mines: u8 },
+
+    /// This is a hidden mine
+    HiddenMine,
+
+    /// This spot is empty, but was flagged as a mine
+    FlaggedEmpty { neighboring_mines: u8 },
+
+    /// This spot contains a mine which was correctly flagged
+    FlaggedMine,
+
+    /// This spot is empty and was correctly revealed
+    RevealedEmpty { neighboring_mines: u8 },
+
+    /// This spot contains a mine and was incorrectly revealed
+    ExplodedMine,
+}
+
+/// Spot struct describing the characteristics of the minefield at a particular position
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Spot {
+    pub state: SpotState,
+
+    /// Number of mines in this spot
+    pub mines: u8,
+
+    /// Number of flags placed on this spot
+    pub flags: u8,
+}
+
+impl Spot {
+    /// Step on this spot, if possible
+    fn step(&mut self) -> StepResult {
+        match self.state {
+            SpotState::HiddenEmpty { neighboring_mines } => {
+                self.state = SpotState::RevealedEmpty { neighboring_mines };
+                StepResult::Phew
+            }
+            SpotState::HiddenMine => {
+                self.state = SpotState::ExplodedMine;
+                StepResult::Boom
+            }
+            _ => StepResult::Invalid,
+        }
+    }
+
+    /// Add a flag to this spot, if possible. Once the spot holds `max_flags` flags, remove them all instead.
+    fn flag(&mut self, max_flags: u8) -> FlagToggleResult {
+        match self.state {
+            SpotState::HiddenEmpty { neighboring_mines } => {
+                self.state = SpotState::FlaggedEmpty { neighboring_mines };
+                self.flags = 1;
+                FlagToggleResult::Added
+            }
+            SpotState::HiddenMine => {
+                self.state = SpotState::FlaggedMine;
+                self.flags = 1;
+                FlagToggleResult::Added
+            }
+            SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine if self.flags < max_flags => {
+                self.flags += 1;
+                FlagToggleResult::Added
+            }
+            SpotState::FlaggedEmpty { neighboring_mines } => {
+                self.state = SpotState::HiddenEmpty { neighboring_mines };
+                self.flags = 0;
+                FlagToggleResult::Removed
+            }
+            SpotState::FlaggedMine => {
+                self.state = SpotState::HiddenMine;
+                self.flags = 0;
+                FlagToggleResult::Removed
+            }
+            _ => FlagToggleResult::None,
+        }
+    }
+
+    /// Has this spot been cleared (either correctly flagged or correctly revealed)?
+    fn is_resolved(&self) -> bool {
+        match self.state {
+            SpotState::FlaggedMine => self.flags == self.mines,
+            SpotState::RevealedEmpty { neighboring_mines: _ } => true,
+            _ => false,
+        }
+    }
+}
+
+impl Spot {
+    /// What this spot shows the player. The mines, and whether the flags are right, are only shown once the game is
+    /// finished.
+    pub fn look(&self, is_finished: bool) -> SpotLook {
+        match (self.state, is_finished) {
+            (SpotState::HiddenMine, true) => SpotLook::Mines(self.mines),
+            (SpotState::HiddenEmpty { neighboring_mines: _ } | SpotState::HiddenMine, _) => SpotLook::Hidden,
+            (SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine, false) => {
+                SpotLook::Flags(self.flags)
+            }
+            (SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine, true) => {
+                SpotLook::CheckedFlags {
+                    flags: self.flags,
+                    is_right: self.flags == self.mines,
+                }
+            }
+            (SpotState::RevealedEmpty { neighboring_mines }, _) => SpotLook::Revealed(neighboring_mines),
+            (SpotState::ExplodedMine, _) => SpotLook::Exploded(self.mines),
+        }
+    }
+}
+
+impl Default for Spot {
+    fn default() -> Self {
+        Self {
+            state: SpotState::HiddenEmpty { neighboring_mines: 0 },
+            mines: 0,
+            flags: 0,
+        }
+    }
+}
+
+/// What a spot shows the player, which each frontend draws its own way
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SpotLook {
+    Hidden,
+
+    /// A revealed empty spot, with its number of neighboring mines
+    Revealed(u8),
+
+    /// The flags on a spot, while the game is played
+    Flags(u8),
+
+    /// The flags on a spot once the game is finished, which are right if the spot holds as many mines
+    CheckedFlags {
+        flags: u8,
+        is_right: bool,
+    },
+
+    /// The mines of a hidden spot, once the game is finished
+    Mines(u8),
+
+    /// The mines of the spot which was stepped on
+    Exploded(u8),
+}
+
+impl SpotLook {
+    /// The symbol of a mine or a flag, followed by how many there are in the spot when there is more than one
+    pub fn counted(symbol: &str, count: u8) -> String {
+        if count > 1 {
+            format!("{}{}", symbol, count)
+        } else {
+            symbol.to_owned()
+        }
+    }
+}
+
+/// The result of stepping on a spot in the minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StepResult {
+    /// Stepped on empty spot
+    Phew,
+
+    /// Stepped on a mine
+    Boom,
+
+    /// Step not taken
+    Invalid,
+}
+
+/// The result of toggling a flag in the mine field
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FlagToggleResult {
+    /// Existing flags were removed
+    Removed,
+    /// A flag was added
+    Added,
+    /// No flag placed or removed
+    None,
+}
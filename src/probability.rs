@@ -0,0 +1,345 @@
+use crate::minefield::{Minefield, SpotState};
+use crate::solver::{self, Constraint, Deduction};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Compute, for every unrevealed (hidden or flagged) spot, the exact probability that it contains a mine, given the
+/// revealed spots and the total number of mines. Flags are not trusted, since the player may have placed them
+/// incorrectly.
+///
+/// Every arrangement of mines which agrees with what is visible is considered equally likely. The unrevealed spots
+/// bordering revealed spots (the frontier) are split into independent groups, the arrangements of each group are
+/// enumerated, and each arrangement is then weighted by the number of ways the remaining mines can be spread over the
+/// spots which are not on the frontier.
+///
+/// Returns `None` if the minefield has no mines yet, if a spot can hold more than one mine, or if the frontier is too
+/// tangled to be enumerated in reasonable time.
+pub fn mine_probabilities(minefield: &Minefield) -> Option<HashMap<(u16, u16), f64>> {
+    if minefield.mines() == 0 || minefield.mines_per_spot() > 1 {
+        return None;
+    }
+
+    let mut probabilities = HashMap::new();
+
+    // Whatever can be deduced needs no enumeration
+    let known = solver::deduce_all(minefield);
+    for (coords, deduction) in &known {
+        let probability = match deduction {
+            Deduction::Safe => 0.0,
+            Deduction::Mine => 1.0,
+        };
+        probabilities.insert(*coords, probability);
+    }
+
+    let constraints = solver::constraints(minefield, &known);
+    let frontier: HashSet<(u16, u16)> = constraints.iter().flat_map(|c| c.spots.iter().copied()).collect();
+
+    let unrevealed: Vec<(u16, u16)> = (0..minefield.height())
+        .flat_map(|y| (0..minefield.width()).map(move |x| (x, y)))
+        .filter(|(x, y)| {
+            !matches!(
+                minefield.spot(*x, *y).unwrap().state,
+                SpotState::RevealedEmpty { neighboring_mines: _ } | SpotState::ExplodedMine
+            )
+        })
+        .collect();
+
+    let interior: Vec<(u16, u16)> = unrevealed
+        .iter()
+        .filter(|coords| !known.contains_key(coords) && !frontier.contains(coords))
+        .copied()
+        .collect();
+
+    let known_mines = known.values().filter(|d| **d == Deduction::Mine).count();
+    let remaining_mines = (minefield.mines() as usize).saturating_sub(known_mines);
+
+    let mut components = vec![];
+    for component_constraints in split_components(constraints) {
+        components.push(Component::enumerate(component_constraints)?);
+    }
+
+    // Number of ways to spread the mines which are not on the frontier over the interior spots, relative to the
+    // largest such number (so that huge minefields don't overflow)
+    let ln_factorials: Vec<f64> = (0..=interior.len())
+        .scan(0.0, |ln_factorial, n| {
+            if n > 0 {
+                *ln_factorial += (n as f64).ln();
+            }
+            Some(*ln_factorial)
+        })
+        .collect();
+    let ln_interior_ways = |mines: usize| -> Option<f64> {
+        (mines <= interior.len())
+            .then(|| ln_factorials[interior.len()] - ln_factorials[mines] - ln_factorials[interior.len() - mines])
+    };
+    let ln_max = (0..=remaining_mines)
+        .filter_map(ln_interior_ways)
+        .fold(f64::MIN, f64::max);
+    let interior_ways = |mines: usize| -> f64 {
+        ln_interior_ways(mines)
+            .map(|ln_ways| (ln_ways - ln_max).exp())
+            .unwrap_or(0.0)
+    };
+
+    // Probability of the spots of each frontier group, knowing all possible mine counts of the other groups
+    for (i, component) in components.iter().enumerate() {
+        let others = components
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(vec![1.0], |distribution, (_, other)| {
+                convolve(&distribution, &other.solutions)
+            });
+
+        // Weight of this group holding a given number of mines
+        let weights: Vec<f64> = (0..component.solutions.len())
+            .map(|mines| {
+                others
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_mines, _)| mines + other_mines <= remaining_mines)
+                    .map(|(other_mines, ways)| ways * interior_ways(remaining_mines - mines - other_mines))
+                    .sum()
+            })
+            .collect();
+
+        let total: f64 = component
+            .solutions
+            .iter()
+            .zip(&weights)
+            .map(|(ways, weight)| ways * weight)
+            .sum();
+
+        for (s, coords) in component.spots.iter().enumerate() {
+            let mined: f64 = component
+                .spot_solutions
+                .iter()
+                .zip(&weights)
+                .map(|(spot_ways, weight)| spot_ways[s] * weight)
+                .sum();
+
+            probabilities.insert(*coords, if total > 0.0 { mined / total } else { 0.0 });
+        }
+    }
+
+    // The interior spots all share the same probability
+    if !interior.is_empty() {
+        let frontier_distribution = components
+            .iter()
+            .fold(vec![1.0], |distribution, c| convolve(&distribution, &c.solutions));
+
+        let (mut total, mut interior_mines) = (0.0, 0.0);
+        for (frontier_mines, ways) in frontier_distribution.iter().enumerate() {
+            if frontier_mines <= remaining_mines {
+                let weight = ways * interior_ways(remaining_mines - frontier_mines);
+                total += weight;
+                interior_mines += weight * (remaining_mines - frontier_mines) as f64;
+            }
+        }
+
+        let probability = if total > 0.0 {
+            interior_mines / total / interior.len() as f64
+        } else {
+            0.0
+        };
+
+        for coords in interior {
+            probabilities.insert(coords, probability);
+        }
+    }
+
+    Some(probabilities)
+}
+
+/// Split the constraints into groups which share no spots, so that each group can be enumerated on its own
+fn split_components(constraints: Vec<Constraint>) -> Vec<Vec<Constraint>> {
+    let mut spot_constraints: HashMap<(u16, u16), Vec<usize>> = HashMap::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for coords in &constraint.spots {
+            spot_constraints.entry(*coords).or_default().push(i);
+        }
+    }
+
+    let mut component_of = vec![usize::MAX; constraints.len()];
+    let mut component_count = 0;
+
+    for start in 0..constraints.len() {
+        if component_of[start] != usize::MAX {
+            continue;
+        }
+
+        let mut to_visit = vec![start];
+        component_of[start] = component_count;
+
+        while let Some(i) = to_visit.pop() {
+            for coords in &constraints[i].spots {
+                for j in &spot_constraints[coords] {
+                    if component_of[*j] == usize::MAX {
+                        component_of[*j] = component_count;
+                        to_visit.push(*j);
+                    }
+                }
+            }
+        }
+
+        component_count += 1;
+    }
+
+    let mut components: Vec<Vec<Constraint>> = (0..component_count).map(|_| vec![]).collect();
+    for (constraint, component) in constraints.into_iter().zip(component_of) {
+        components[component].push(constraint);
+    }
+
+    components
+}
+
+/// Number of ways in which `a` and `b` together can hold a given number of mines, knowing the number of ways in which
+/// each of them can hold a given number of mines
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; a.len() + b.len() - 1];
+
+    for (i, a_ways) in a.iter().enumerate() {
+        for (j, b_ways) in b.iter().enumerate() {
+            result[i + j] += a_ways * b_ways;
+        }
+    }
+
+    result
+}
+
+/// All the arrangements of mines in a group of frontier spots which satisfy the group's constraints
+struct Component {
+    /// The spots in this group
+    spots: Vec<(u16, u16)>,
+
+    /// Number of arrangements holding a given number of mines (the index)
+    solutions: Vec<f64>,
+
+    /// For each number of mines (the index), the number of arrangements in which each spot holds a mine
+    spot_solutions: Vec<Vec<f64>>,
+}
+
+impl Component {
+    /// Give up on groups which need more steps than this to enumerate
+    const MAX_STEPS: usize = 5_000_000;
+
+    fn enumerate(constraints: Vec<Constraint>) -> Option<Self> {
+        // Visit spots in breadth-first order, so that constraints are completely assigned (and checked) as early as
+        // possible
+        let mut spots: Vec<(u16, u16)> = vec![];
+        let mut visited = HashSet::new();
+        let mut to_visit: VecDeque<(u16, u16)> = constraints[0].spots.iter().copied().collect();
+
+        while let Some(coords) = to_visit.pop_front() {
+            if visited.insert(coords) {
+                spots.push(coords);
+
+                for constraint in constraints.iter().filter(|c| c.spots.contains(&coords)) {
+                    to_visit.extend(constraint.spots.iter().filter(|s| !visited.contains(s)));
+                }
+            }
+        }
+
+        let spot_constraints: Vec<Vec<usize>> = spots
+            .iter()
+            .map(|coords| {
+                (0..constraints.len())
+                    .filter(|c| constraints[*c].spots.contains(coords))
+                    .collect()
+            })
+            .collect();
+
+        let mut enumeration = Enumeration {
+            required: constraints.iter().map(|c| c.mines).collect(),
+            placed: vec![0; constraints.len()],
+            unassigned: constraints.iter().map(|c| c.spots.len()).collect(),
+            spot_constraints,
+            mined: vec![false; spots.len()],
+            solutions: vec![0.0; spots.len() + 1],
+            spot_solutions: vec![vec![0.0; spots.len()]; spots.len() + 1],
+            steps: 0,
+        };
+
+        if enumeration.assign(0) {
+            Some(Self {
+                spots,
+                solutions: enumeration.solutions,
+                spot_solutions: enumeration.spot_solutions,
+            })
+        } else {
+            tracing::debug!("Gave up enumerating a group of {} frontier spots", spots.len());
+            None
+        }
+    }
+}
+
+/// Backtracking state used while enumerating the arrangements of a group of frontier spots
+struct Enumeration {
+    /// Mines required by each constraint
+    required: Vec<usize>,
+
+    /// Mines placed so far in the spots of each constraint
+    placed: Vec<usize>,
+
+    /// Spots of each constraint not yet assigned
+    unassigned: Vec<usize>,
+
+    /// Constraints of each spot
+    spot_constraints: Vec<Vec<usize>>,
+
+    /// Current arrangement
+    mined: Vec<bool>,
+
+    solutions: Vec<f64>,
+    spot_solutions: Vec<Vec<f64>>,
+    steps: usize,
+}
+
+impl Enumeration {
+    /// Try both a mine and an empty spot at the given spot index, and recurse. Returns `false` if the enumeration
+    /// took too long.
+    fn assign(&mut self, spot: usize) -> bool {
+        self.steps += 1;
+        if self.steps > Component::MAX_STEPS {
+            return false;
+        }
+
+        if spot == self.mined.len() {
+            let mines = self.mined.iter().filter(|m| **m).count();
+            self.solutions[mines] += 1.0;
+            for (s, mined) in self.mined.iter().enumerate() {
+                if *mined {
+                    self.spot_solutions[mines][s] += 1.0;
+                }
+            }
+            return true;
+        }
+
+        for mined in [false, true] {
+            self.mined[spot] = mined;
+
+            let mut is_consistent = true;
+            for c in &self.spot_constraints[spot] {
+                self.unassigned[*c] -= 1;
+                self.placed[*c] += mined as usize;
+
+                if self.placed[*c] > self.required[*c] || self.placed[*c] + self.unassigned[*c] < self.required[*c] {
+                    is_consistent = false;
+                }
+            }
+
+            let is_within_budget = !is_consistent || self.assign(spot + 1);
+
+            for c in &self.spot_constraints[spot] {
+                self.unassigned[*c] += 1;
+                self.placed[*c] -= mined as usize;
+            }
+
+            if !is_within_budget {
+                return false;
+            }
+        }
+
+        self.mined[spot] = false;
+        true
+    }
+}
@@ -0,0 +1,134 @@
+use crate::game::{Game, GameConfig, GameState, Seed};
+use crate::highscores::{DailyHighScores, HighScore, HighScores};
+use crate::replay::Replay;
+use crate::statistics::{GameRecord, Statistics};
+
+/// Everything kept about finished games: the high scores, the statistics and the latest replays. Every frontend records
+/// its games here, so that they all count them the same way.
+#[derive(Debug, Clone, Default)]
+pub struct Records {
+    pub high_scores: HighScores,
+    pub daily_high_scores: DailyHighScores,
+    pub statistics: Statistics,
+    /// The most recent replays, newest first
+    pub replays: Vec<Replay>,
+    /// Games finished since the records were last saved, which are added again to the records in storage if another
+    /// app changed them in the meantime
+    unsaved: Vec<FinishedGame>,
+}
+
+impl Records {
+    /// Maximum number of replays kept
+    pub const MAX_REPLAYS: usize = 10;
+
+    const HIGH_SCORES_KEY: &str = "high scores";
+    const DAILY_HIGH_SCORES_KEY: &str = "daily high scores";
+    const STATISTICS_KEY: &str = "statistics";
+    const REPLAYS_KEY: &str = "replays";
+
+    /// Read the records from storage. Records which can't be read are empty.
+    pub fn load(storage: &dyn eframe::Storage) -> Self {
+        Self {
+            high_scores: eframe::get_value(storage, Self::HIGH_SCORES_KEY).unwrap_or_default(),
+            daily_high_scores: eframe::get_value(storage, Self::DAILY_HIGH_SCORES_KEY).unwrap_or_default(),
+            statistics: eframe::get_value(storage, Self::STATISTICS_KEY).unwrap_or_default(),
+            replays: eframe::get_value(storage, Self::REPLAYS_KEY).unwrap_or_default(),
+            unsaved: vec![],
+        }
+    }
+
+    /// Take the records in storage instead of these, which another app (sharing the storage) may have changed since
+    /// these were loaded, and add to them the games finished since these were last saved
+    pub fn merge_stored(&mut self, storage: &dyn eframe::Storage) {
+        let mut records = Self::load(storage);
+        for game in &self.unsaved {
+            records.record(game);
+        }
+
+        records.unsaved = std::mem::take(&mut self.unsaved);
+        *self = records;
+    }
+
+    pub fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, Self::HIGH_SCORES_KEY, &self.high_scores);
+        eframe::set_value(storage, Self::DAILY_HIGH_SCORES_KEY, &self.daily_high_scores);
+        eframe::set_value(storage, Self::STATISTICS_KEY, &self.statistics);
+        eframe::set_value(storage, Self::REPLAYS_KEY, &self.replays);
+        self.unsaved.clear();
+    }
+
+    /// Record a finished game: its replay, and unless it is a practice game, its statistics and high score. Returns the
+    /// rank (starting from `0`) of the new high score, if the game made it into the high scores.
+    pub fn add(&mut self, game: &FinishedGame) -> Option<usize> {
+        self.unsaved.push(game.clone());
+        self.record(game)
+    }
+
+    /// Forget the last finished game, which came back to life when its last move was undone. Only its replay was
+    /// recorded, since only practice games can be undone once lost.
+    pub fn remove_last_replay(&mut self) {
+        if !self.replays.is_empty() {
+            self.replays.remove(0);
+        }
+        self.unsaved.pop();
+    }
+
+    fn record(&mut self, game: &FinishedGame) -> Option<usize> {
+        self.replays.insert(0, game.replay.clone());
+        self.replays.truncate(Self::MAX_REPLAYS);
+
+        // Practice games are played with help, and don't count
+        if game.is_practice {
+            return None;
+        }
+
+        let record = GameRecord::new(game.is_won, game.millis, game.clicks, game.bbbv);
+        self.statistics.add(&game.config, record);
+
+        if !game.is_won {
+            return None;
+        }
+
+        let score = HighScore::new(&game.replay.player, game.millis, game.hints_used);
+        if game.config.seed == Seed::Daily {
+            self.daily_high_scores.add(game.seed, &game.config, score)
+        } else {
+            self.high_scores.add(&game.config, score)
+        }
+    }
+}
+
+/// What is recorded about a finished game
+#[derive(Debug, Clone)]
+pub struct FinishedGame {
+    pub config: GameConfig,
+    pub replay: Replay,
+    pub is_won: bool,
+    pub is_practice: bool,
+    pub millis: u64,
+    pub clicks: u32,
+    pub bbbv: u32,
+    pub hints_used: u32,
+    /// Seed of the minefield, which is the day of a daily game
+    pub seed: u64,
+}
+
+impl FinishedGame {
+    /// The record of a game which just finished, played by the given player
+    pub fn of(game: &Game, player_name: &str) -> Self {
+        let mut replay = game.replay().clone();
+        replay.player = player_name.to_owned();
+
+        Self {
+            config: *game.config(),
+            replay,
+            is_won: game.state() == GameState::Stopped(true),
+            is_practice: game.is_practice(),
+            millis: game.millis(),
+            clicks: game.clicks(),
+            bbbv: game.bbbv(),
+            hints_used: game.hints_used(),
+            seed: game.seed(),
+        }
+    }
+}
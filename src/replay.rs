@@ -0,0 +1,205 @@
+use crate::game::GameConfig;
+use crate::minefield::{FlagToggleResult, Minefield, StepResult};
+use serde::{Deserialize, Serialize};
+
+/// Recording of a game: the minefield it was played on, and everything the player did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub config: GameConfig,
+
+    /// Coordinates of the mines, as they were placed when the first step was taken
+    pub mines: Vec<(u16, u16)>,
+
+    /// The player's actions, in the order they were taken
+    pub actions: Vec<ReplayAction>,
+
+    /// Name of the player
+    pub player: String,
+
+    /// Local date and time when the game ended, formatted for display
+    pub date: String,
+
+    /// Was the game won (`true`), or lost (`false`)
+    pub is_won: bool,
+}
+
+impl Replay {
+    pub fn new(config: GameConfig, player: &str) -> Self {
+        Self {
+            config,
+            mines: vec![],
+            actions: vec![],
+            player: player.to_owned(),
+            date: String::new(),
+            is_won: false,
+        }
+    }
+
+    /// Remember an action taken by the player, `millis` milliseconds after the game timer was started
+    pub fn record(&mut self, millis: u64, action: Action) {
+        self.actions.push(ReplayAction { millis, action });
+    }
+
+    /// Mark the end of the game, dated now
+    pub fn finish(&mut self, is_won: bool) {
+        self.is_won = is_won;
+        self.date = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    }
+
+    /// Milliseconds from the start of the game to the last action
+    pub fn duration_millis(&self) -> u64 {
+        self.actions.last().map(|a| a.millis).unwrap_or(0)
+    }
+
+    /// Encode the replay as text, which can be saved to a file or shared
+    pub fn to_text(&self) -> String {
+        ron::to_string(self).expect("Replays can always be serialized")
+    }
+
+    /// Decode a replay which was encoded with `to_text`
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let replay: Self = ron::from_str(text.trim()).map_err(|e| format!("Not a valid replay: {}", e))?;
+
+        if !replay.config.is_valid() {
+            return Err(format!("Not a valid replay config: {:?}", replay.config));
+        }
+
+        Ok(replay)
+    }
+}
+
+/// One action taken by the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayAction {
+    /// Milliseconds since the game timer was started
+    pub millis: u64,
+    pub action: Action,
+}
+
+/// What the player did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Step(u16, u16),
+    AutoStep(u16, u16),
+    ToggleFlag(u16, u16),
+    Undo,
+    Redo,
+}
+
+/// Plays back a replay, one action at a time
+pub struct ReplayViewer {
+    pub replay: Replay,
+
+    /// The minefield, as it is at the current playback position
+    pub minefield: Minefield,
+
+    /// Milliseconds played back so far
+    pub position: f64,
+
+    /// Playback speed, where `1.0` is the speed at which the game was played
+    pub speed: f32,
+
+    pub is_playing: bool,
+
+    /// Number of actions applied to the minefield so far
+    applied: usize,
+
+    /// Has the first step been applied? Moves made before it could not be undone.
+    is_started: bool,
+
+    undo_history: Vec<Minefield>,
+    redo_history: Vec<Minefield>,
+}
+
+impl ReplayViewer {
+    pub fn new(replay: Replay) -> Self {
+        let minefield = Self::initial_minefield(&replay);
+        Self {
+            replay,
+            minefield,
+            position: 0.0,
+            speed: 1.0,
+            is_playing: true,
+            applied: 0,
+            is_started: false,
+            undo_history: vec![],
+            redo_history: vec![],
+        }
+    }
+
+    /// Go back to the beginning of the game
+    pub fn rewind(&mut self) {
+        self.minefield = Self::initial_minefield(&self.replay);
+        self.position = 0.0;
+        self.applied = 0;
+        self.is_started = false;
+        self.undo_history.clear();
+        self.redo_history.clear();
+    }
+
+    /// Has every action been played back?
+    pub fn is_finished(&self) -> bool {
+        self.applied == self.replay.actions.len()
+    }
+
+    /// Move the playback position forward by the given real time, and apply all the actions reached
+    pub fn advance(&mut self, seconds: f32) {
+        if self.is_playing {
+            self.position += seconds as f64 * self.speed as f64 * 1000.0;
+        }
+
+        while let Some(replay_action) = self.replay.actions.get(self.applied) {
+            if replay_action.millis as f64 > self.position {
+                break;
+            }
+
+            self.apply(replay_action.action);
+            self.applied += 1;
+        }
+
+        if self.is_finished() {
+            self.is_playing = false;
+        }
+    }
+
+    fn initial_minefield(replay: &Replay) -> Minefield {
+        replay.config.empty_minefield().with_mines_at(&replay.mines)
+    }
+
+    /// Apply an action the same way the game did, including its undo rules: only the moves which changed the
+    /// minefield after the first step can be undone (see `Game::undo`)
+    fn apply(&mut self, action: Action) {
+        let snapshot = self.is_started.then(|| self.minefield.clone());
+
+        let is_changed = match action {
+            Action::Step(x, y) => {
+                self.is_started = true;
+                self.minefield.step(x, y) != StepResult::Invalid
+            }
+            Action::AutoStep(x, y) => {
+                let has_hidden_neighbors = self.minefield.has_hidden_neighbors(x, y);
+                self.minefield.auto_step(x, y) != StepResult::Invalid && has_hidden_neighbors
+            }
+            Action::ToggleFlag(x, y) => self.minefield.toggle_flag(x, y) != FlagToggleResult::None,
+            Action::Undo => {
+                if let Some(minefield) = self.undo_history.pop() {
+                    self.redo_history
+                        .push(std::mem::replace(&mut self.minefield, minefield));
+                }
+                return;
+            }
+            Action::Redo => {
+                if let Some(minefield) = self.redo_history.pop() {
+                    self.undo_history
+                        .push(std::mem::replace(&mut self.minefield, minefield));
+                }
+                return;
+            }
+        };
+
+        if is_changed {
+            self.undo_history.extend(snapshot);
+            self.redo_history.clear();
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::app::MinesweepRsApp;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The file where eframe keeps the data of the native egui app, as a map of RON values. The terminal app keeps its data
+/// in the same file, and both apps read it again before saving, so that neither loses the games the other one recorded
+/// in the meantime (see `Records::merge_stored`).
+pub struct SettingsFile {
+    /// Path of the file, unless there is nowhere to store it
+    path: Option<PathBuf>,
+    kv: HashMap<String, String>,
+}
+
+impl SettingsFile {
+    /// The file at the same place as eframe keeps it for the egui app, as it is now. Returns `None` if it can't be read
+    /// (see `reload`).
+    pub fn open() -> Option<Self> {
+        let path = directories_next::ProjectDirs::from("", "", MinesweepRsApp::TITLE)
+            .map(|dirs| dirs.data_dir().join("app.ron"));
+
+        Self::open_path(path)
+    }
+
+    /// The file at the given path, as it is now. Returns `None` if it can't be read (see `reload`).
+    pub fn at(path: PathBuf) -> Option<Self> {
+        Self::open_path(Some(path))
+    }
+
+    /// Where the file is, unless there is nowhere to store it
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    fn open_path(path: Option<PathBuf>) -> Option<Self> {
+        let mut file = Self {
+            path,
+            kv: HashMap::new(),
+        };
+        file.reload().then_some(file)
+    }
+
+    /// Read the file again, forgetting what was set since it was last read. A file which doesn't exist yet is empty.
+    /// Returns `false` if the file can't be read, for instance because the other app is writing it right now, and then
+    /// what was read before is kept.
+    pub fn reload(&mut self) -> bool {
+        let Some(path) = &self.path else {
+            return true;
+        };
+
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.kv.clear();
+                return true;
+            }
+            Err(e) => {
+                tracing::warn!("Could not read {}: {}", path.display(), e);
+                return false;
+            }
+        };
+
+        match ron::from_str(&text) {
+            Ok(kv) => {
+                self.kv = kv;
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Could not read {}: {}", path.display(), e);
+                false
+            }
+        }
+    }
+}
+
+impl eframe::Storage for SettingsFile {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.kv.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.kv.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| {
+                let text = ron::ser::to_string_pretty(&self.kv, Default::default()).map_err(std::io::Error::other)?;
+                std::fs::write(path, text)
+            });
+
+        if let Err(e) = result {
+            eprintln!("Could not save to {}: {}", path.display(), e);
+        }
+    }
+}
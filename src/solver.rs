@@ -0,0 +1,232 @@
+use crate::minefield::{Minefield, SpotState, StepResult};
+use std::collections::{HashMap, HashSet};
+
+/// What can be logically deduced about a spot, from the visible state of the minefield
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Deduction {
+    /// The spot is certainly empty
+    Safe,
+
+    /// The spot certainly contains a mine
+    Mine,
+}
+
+/// Find all the spots whose content can be deduced from what a player can see in the minefield (the revealed spots,
+/// and the total number of mines), and where the player has yet to act on that knowledge: hidden spots which are
+/// either safe or mines, and flagged spots which are actually safe.
+///
+/// Flags are never trusted, since the player may have placed them incorrectly.
+///
+/// The deductions are returned in reading order (top-left to bottom-right).
+pub fn deduce(minefield: &Minefield) -> Vec<((u16, u16), Deduction)> {
+    let known = deduce_all(minefield);
+
+    let mut actionable: Vec<((u16, u16), Deduction)> = known
+        .into_iter()
+        .filter(
+            |((x, y), deduction)| match (minefield.spot(*x, *y).unwrap().state, deduction) {
+                (SpotState::FlaggedEmpty { neighboring_mines: _ } | SpotState::FlaggedMine, Deduction::Mine) => false,
+                (_, _) => true,
+            },
+        )
+        .collect();
+
+    actionable.sort_by_key(|((x, y), _)| (*y, *x));
+    actionable
+}
+
+/// Check if the minefield can be cleared by pure logic (i.e. without ever having to guess), after taking a first step
+/// at the given coordinates
+pub fn is_solvable_from(minefield: &Minefield, x: u16, y: u16) -> bool {
+    let mut minefield = minefield.clone();
+
+    if minefield.step(x, y) != StepResult::Phew {
+        return false;
+    }
+
+    loop {
+        let deductions = deduce(&minefield);
+
+        if deductions.is_empty() {
+            break;
+        }
+
+        for ((dx, dy), deduction) in deductions {
+            match deduction {
+                Deduction::Safe => {
+                    let _step_result = minefield.step(dx, dy);
+                    assert_ne!(_step_result, StepResult::Boom);
+                }
+                Deduction::Mine => {
+                    minefield.toggle_flag(dx, dy);
+                }
+            }
+        }
+    }
+
+    minefield.is_cleared()
+}
+
+/// Deduce the content of as many unrevealed (hidden or flagged) spots as possible. Nothing is deduced in minefields
+/// where a spot can hold more than one mine.
+pub(crate) fn deduce_all(minefield: &Minefield) -> HashMap<(u16, u16), Deduction> {
+    if minefield.mines_per_spot() > 1 {
+        return HashMap::new();
+    }
+
+    let is_unrevealed = |x: u16, y: u16| {
+        !matches!(
+            minefield.spot(x, y).unwrap().state,
+            SpotState::RevealedEmpty { neighboring_mines: _ } | SpotState::ExplodedMine
+        )
+    };
+
+    let mut known: HashMap<(u16, u16), Deduction> = HashMap::new();
+
+    loop {
+        let known_count = known.len();
+        let constraints = constraints(minefield, &known);
+
+        // A constraint which is either empty or full of mines says everything about its spots
+        for constraint in &constraints {
+            constraint.deduce_trivial(&mut known);
+        }
+
+        // Two overlapping constraints may say something about the spots they don't share
+        let mut spot_constraints: HashMap<(u16, u16), Vec<usize>> = HashMap::new();
+        for (i, constraint) in constraints.iter().enumerate() {
+            for coords in &constraint.spots {
+                spot_constraints.entry(*coords).or_default().push(i);
+            }
+        }
+
+        let mut visited_pairs = HashSet::new();
+        for indices in spot_constraints.values() {
+            for (n, i) in indices.iter().enumerate() {
+                for j in &indices[n + 1..] {
+                    if visited_pairs.insert((*i, *j)) {
+                        Constraint::deduce_overlap(&constraints[*i], &constraints[*j], &mut known);
+                        Constraint::deduce_overlap(&constraints[*j], &constraints[*i], &mut known);
+                    }
+                }
+            }
+        }
+
+        // As a last resort, look at the total number of mines left in the whole minefield
+        if known.len() == known_count {
+            let unknown: Vec<(u16, u16)> = (0..minefield.height())
+                .flat_map(|y| (0..minefield.width()).map(move |x| (x, y)))
+                .filter(|(x, y)| is_unrevealed(*x, *y) && !known.contains_key(&(*x, *y)))
+                .collect();
+
+            let known_mines = known.values().filter(|d| **d == Deduction::Mine).count();
+            let remaining_mines = (minefield.mines() as usize).saturating_sub(known_mines);
+
+            Constraint {
+                spots: unknown,
+                mines: remaining_mines,
+            }
+            .deduce_trivial(&mut known);
+        }
+
+        if known.len() == known_count {
+            // Nothing new was learned
+            break;
+        }
+    }
+
+    known
+}
+
+/// Gather the constraints imposed by the revealed spots on their unrevealed neighbors, ignoring the spots which are
+/// already `known`
+pub(crate) fn constraints(minefield: &Minefield, known: &HashMap<(u16, u16), Deduction>) -> Vec<Constraint> {
+    let mut constraints = vec![];
+
+    for y in 0..minefield.height() {
+        for x in 0..minefield.width() {
+            if let SpotState::RevealedEmpty { neighboring_mines } = minefield.spot(x, y).unwrap().state {
+                let mut spots = vec![];
+                let mut known_mines = 0;
+
+                for (nx, ny) in minefield.neighbors_coords(x, y) {
+                    match known.get(&(nx, ny)) {
+                        Some(Deduction::Mine) => known_mines += 1,
+                        Some(Deduction::Safe) => {}
+                        None => match minefield.spot(nx, ny).unwrap().state {
+                            SpotState::RevealedEmpty { neighboring_mines: _ } => {}
+                            SpotState::ExplodedMine => known_mines += 1,
+                            _ => spots.push((nx, ny)),
+                        },
+                    }
+                }
+
+                if !spots.is_empty() {
+                    let mines = (neighboring_mines as usize).saturating_sub(known_mines);
+                    constraints.push(Constraint { spots, mines });
+                }
+            }
+        }
+    }
+
+    constraints
+}
+
+/// Exactly `mines` of the `spots` contain a mine
+#[derive(Debug)]
+pub(crate) struct Constraint {
+    pub spots: Vec<(u16, u16)>,
+    pub mines: usize,
+}
+
+impl Constraint {
+    /// If there are no mines, then all spots are safe. If there are as many mines as spots, then all spots are mines.
+    fn deduce_trivial(&self, known: &mut HashMap<(u16, u16), Deduction>) {
+        let deduction = if self.mines == 0 {
+            Deduction::Safe
+        } else if self.mines == self.spots.len() {
+            Deduction::Mine
+        } else {
+            return;
+        };
+
+        for coords in &self.spots {
+            known.insert(*coords, deduction);
+        }
+    }
+
+    /// Deduce what is possible about the spots of `a` which are not shared with `b`, knowing that the shared spots
+    /// hold at least as many mines as `a` and `b` require of them, but no more than either `a` or `b` allows
+    fn deduce_overlap(a: &Constraint, b: &Constraint, known: &mut HashMap<(u16, u16), Deduction>) {
+        let a_only: Vec<(u16, u16)> = a.spots.iter().filter(|s| !b.spots.contains(s)).copied().collect();
+        let b_only_len = b.spots.iter().filter(|s| !a.spots.contains(s)).count();
+
+        if a_only.is_empty() {
+            return;
+        }
+
+        let shared_min = a
+            .mines
+            .saturating_sub(a_only.len())
+            .max(b.mines.saturating_sub(b_only_len));
+        let shared_max = a.mines.min(b.mines).min(a.spots.len() - a_only.len());
+
+        // Range of the number of mines in the spots which only belong to `a`
+        let a_only_min = a.mines.saturating_sub(shared_max);
+        let a_only_max = a.mines.saturating_sub(shared_min);
+
+        if a_only_max == 0 {
+            Constraint {
+                spots: a_only,
+                mines: 0,
+            }
+            .deduce_trivial(known);
+        } else if a_only_min == a_only.len() {
+            Constraint {
+                mines: a_only.len(),
+                spots: a_only,
+            }
+            .deduce_trivial(known);
+        }
+    }
+}
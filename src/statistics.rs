@@ -0,0 +1,151 @@
+use crate::game::GameConfig;
+use serde::{Deserialize, Serialize};
+
+/// Statistics of finished games, with one table for each game configuration that was ever played
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Statistics {
+    tables: Vec<StatisticsTable>,
+}
+
+impl Statistics {
+    /// Maximum number of games kept in each table. Older games are forgotten, but still count in the totals.
+    pub const MAX_GAMES: usize = 1000;
+
+    /// Record a finished game
+    pub fn add(&mut self, config: &GameConfig, game: GameRecord) {
+        let table = if let Some(pos) = self.tables.iter().position(|t| t.config == *config) {
+            &mut self.tables[pos]
+        } else {
+            self.tables.push(StatisticsTable {
+                config: *config,
+                played: 0,
+                won: 0,
+                current_streak: 0,
+                best_streak: 0,
+                games: vec![],
+            });
+            self.tables.last_mut().unwrap()
+        };
+
+        table.played += 1;
+
+        if game.is_won {
+            table.won += 1;
+            table.current_streak += 1;
+            table.best_streak = table.best_streak.max(table.current_streak);
+        } else {
+            table.current_streak = 0;
+        }
+
+        table.games.push(game);
+        if table.games.len() > Self::MAX_GAMES {
+            table.games.remove(0);
+        }
+    }
+
+    /// The statistics recorded for the given game configuration, if any game was played with it
+    pub fn table(&self, config: &GameConfig) -> Option<&StatisticsTable> {
+        self.tables.iter().find(|t| t.config == *config)
+    }
+
+    /// Iterator over all the game configurations which have at least one recorded game
+    pub fn configs(&self) -> impl Iterator<Item = &GameConfig> {
+        self.tables.iter().map(|t| &t.config)
+    }
+
+    /// Remove the statistics recorded for the given game configuration
+    pub fn clear(&mut self, config: &GameConfig) {
+        self.tables.retain(|t| t.config != *config);
+    }
+
+    /// Remove all recorded statistics
+    pub fn clear_all(&mut self) {
+        self.tables.clear();
+    }
+}
+
+/// Statistics of the games played with a particular game configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsTable {
+    pub config: GameConfig,
+
+    /// Number of finished games
+    pub played: u32,
+
+    /// Number of won games
+    pub won: u32,
+
+    /// Number of games won in a row, up to the last game
+    pub current_streak: u32,
+
+    /// Largest number of games ever won in a row
+    pub best_streak: u32,
+
+    /// The most recent games, oldest first
+    pub games: Vec<GameRecord>,
+}
+
+impl StatisticsTable {
+    /// Percentage of the finished games which were won
+    pub fn win_percentage(&self) -> f64 {
+        if self.played > 0 {
+            self.won as f64 * 100.0 / self.played as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Distribution of the times of the recent won games, in `bins` intervals of equal width. Returns the width of an
+    /// interval in seconds, and the number of games won within each interval.
+    pub fn win_times_histogram(&self, bins: usize) -> (f64, Vec<u32>) {
+        let win_seconds: Vec<f64> = self
+            .games
+            .iter()
+            .filter(|g| g.is_won)
+            .map(|g| g.millis as f64 / 1000.0)
+            .collect();
+
+        let max_seconds = win_seconds.iter().copied().fold(0.0, f64::max);
+        let bin_width = (max_seconds / bins as f64).ceil().max(1.0);
+
+        let mut histogram = vec![0; bins];
+        for seconds in win_seconds {
+            let bin = ((seconds / bin_width) as usize).min(bins - 1);
+            histogram[bin] += 1;
+        }
+
+        (bin_width, histogram)
+    }
+}
+
+/// One finished game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Was the game won (`true`), or lost (`false`)
+    pub is_won: bool,
+
+    /// Time from the first move to the end of the game
+    pub millis: u64,
+
+    /// Number of moves made by the player (steps, chords and flags)
+    pub clicks: u32,
+
+    /// Minimum number of clicks needed to clear the minefield (see `Minefield::bbbv`)
+    pub bbbv: u32,
+
+    /// Local date and time when the game ended, formatted for display
+    pub date: String,
+}
+
+impl GameRecord {
+    /// Create a new record, dated now
+    pub fn new(is_won: bool, millis: u64, clicks: u32, bbbv: u32) -> Self {
+        Self {
+            is_won,
+            millis,
+            clicks,
+            bbbv,
+            date: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use minesweep_rs::cli::{Args, Theme};
+use minesweep_rs::game::{GameConfig, GameDifficulty, PlayMode, Seed};
+
+fn parse(args: &str) -> Result<Args, String> {
+    Args::parse(args.split_whitespace().map(str::to_owned))
+}
+
+#[test]
+fn no_arguments_keep_saved_config() {
+    let args = parse("").unwrap();
+
+    assert_eq!(args, Args::default());
+    assert_eq!(args.game_config(GameDifficulty::HARD), Ok(None));
+}
+
+#[test]
+fn difficulty_keeps_the_rest_of_the_saved_config() {
+    let saved = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..GameDifficulty::EASY
+    };
+    let config = parse("--difficulty hard").unwrap().game_config(saved).unwrap().unwrap();
+
+    assert_eq!(
+        config,
+        GameConfig {
+            play_mode: PlayMode::Practice,
+            ..GameDifficulty::HARD
+        }
+    );
+}
+
+#[test]
+fn custom_minefield() {
+    let args = parse("--width=20 --height 12 --mines 30 --seed 42").unwrap();
+    let config = args.game_config(GameDifficulty::EASY).unwrap().unwrap();
+
+    assert_eq!((config.width, config.height, config.mines), (20, 12, 30));
+    assert_eq!(config.seed, Seed::Fixed(42));
+    assert!(config.is_valid());
+}
+
+#[test]
+fn reset_config_starts_from_default() {
+    let saved = GameConfig {
+        seed: Seed::Daily,
+        ..GameDifficulty::HARD
+    };
+
+    assert_eq!(
+        parse("--reset-config").unwrap().game_config(saved),
+        Ok(Some(GameConfig::default()))
+    );
+}
+
+#[test]
+fn window_options() {
+    let args = parse("--theme light --fullscreen").unwrap();
+
+    assert_eq!(args.theme, Some(Theme::Light));
+    assert!(args.fullscreen);
+    assert_eq!(args.game_config(GameDifficulty::EASY), Ok(None));
+}
+
+#[test]
+fn saved_mines_are_lowered_to_fit() {
+    let config = parse("--width 5 --height 5")
+        .unwrap()
+        .game_config(GameDifficulty::HARD)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!((config.width, config.height, config.mines), (5, 5, 24));
+    assert!(config.is_valid());
+}
+
+#[test]
+fn too_many_mines_are_an_error() {
+    let args = parse("--mines 120").unwrap();
+
+    assert!(args.game_config(GameDifficulty::HARD).unwrap().is_some());
+    assert!(args.game_config(GameDifficulty::EASY).is_err());
+    assert!(parse("--difficulty easy --mines 100")
+        .unwrap()
+        .game_config(GameDifficulty::HARD)
+        .is_err());
+}
+
+#[test]
+fn invalid_arguments_are_errors() {
+    for args in [
+        "--width 4",
+        "--height many",
+        "--mines 0",
+        "--difficulty extreme",
+        "--theme blue",
+        "--seed",
+        "--fullscreen=yes",
+        "--unknown",
+        "--difficulty easy --mines 300",
+    ] {
+        assert!(parse(args).is_err(), "{}", args);
+    }
+}
@@ -0,0 +1,41 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use minesweep_rs::board::Board;
+use minesweep_rs::game::{Game, GameConfig, GameDifficulty};
+
+/// Config of a small 5x5 minefield with two mines
+pub fn small_config() -> GameConfig {
+    GameConfig {
+        width: 5,
+        height: 5,
+        mines: 2,
+        ..GameDifficulty::EASY
+    }
+}
+
+/// Board of a small minefield, with mines in the top left and bottom right corners:
+///
+/// ```text
+/// * 1 0 0 0
+/// 1 1 0 0 0
+/// 0 0 0 0 0
+/// 0 0 0 1 1
+/// 0 0 0 1 *
+/// ```
+pub fn small_board(config: GameConfig, start: Option<(u16, u16)>) -> Board {
+    Board {
+        config,
+        mines: vec![(0, 0), (4, 4)],
+        start,
+    }
+}
+
+/// A game on the small board, which starts in the middle
+pub fn small_game(config: GameConfig) -> Game {
+    small_game_starting_at(config, (2, 2))
+}
+
+pub fn small_game_starting_at(config: GameConfig, start: (u16, u16)) -> Game {
+    Game::with_board(small_board(config, Some(start)))
+}
@@ -0,0 +1,524 @@
+use minesweep_rs::game::{Game, GameConfig, GameDifficulty, GameEvent, GameState, Generator, PlayMode, Seed};
+use minesweep_rs::minefield::{FlagToggleResult, Heading, Minefield, SpotLook, SpotState, StepResult, Topology};
+use minesweep_rs::replay::ReplayViewer;
+
+mod common;
+use common::{small_board, small_config, small_game, small_game_starting_at};
+
+fn is_revealed(game: &Game, x: u16, y: u16) -> bool {
+    matches!(
+        game.minefield().spot(x, y).unwrap().state,
+        SpotState::RevealedEmpty { neighboring_mines: _ }
+    )
+}
+
+#[test]
+fn game_is_won_on_last_reveal() {
+    let mut game = small_game(small_config());
+
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Added);
+    assert_eq!(game.flag(4, 4), FlagToggleResult::Added);
+    assert_eq!(game.state(), GameState::Running);
+
+    // The opening reveals every empty spot at once
+    assert_eq!(game.reveal(2, 2), StepResult::Phew);
+    assert!(game.minefield().is_cleared());
+    assert_eq!(game.state(), GameState::Stopped(true));
+    assert_eq!(game.take_events().last(), Some(&GameEvent::Finished { is_won: true }));
+}
+
+#[test]
+fn minefield_is_cleared_on_last_reveal() {
+    let mut minefield = Minefield::new(3, 1).with_mines_at(&[(0, 0)]);
+    minefield.toggle_flag(0, 0);
+
+    assert_eq!(minefield.step(1, 0), StepResult::Phew);
+    assert!(!minefield.is_cleared());
+
+    assert_eq!(minefield.step(2, 0), StepResult::Phew);
+    assert!(minefield.is_cleared());
+}
+
+#[test]
+fn game_is_won_on_last_flag() {
+    let mut game = small_game(small_config());
+
+    assert_eq!(game.reveal(2, 2), StepResult::Phew);
+    assert_eq!(game.state(), GameState::Running);
+
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Added);
+    assert!(!game.minefield().is_cleared());
+    assert_eq!(game.state(), GameState::Running);
+
+    assert_eq!(game.flag(4, 4), FlagToggleResult::Added);
+    assert!(game.minefield().is_cleared());
+    assert_eq!(game.state(), GameState::Stopped(true));
+}
+
+#[test]
+fn minefield_is_cleared_on_last_flag() {
+    let mut minefield = Minefield::new(3, 1).with_mines_at(&[(0, 0), (2, 0)]);
+    minefield.step(1, 0);
+
+    assert_eq!(minefield.toggle_flag(0, 0), FlagToggleResult::Added);
+    assert!(!minefield.is_cleared());
+
+    assert_eq!(minefield.toggle_flag(2, 0), FlagToggleResult::Added);
+    assert!(minefield.is_cleared());
+
+    // Removing a flag leaves the minefield unsolved again
+    assert_eq!(minefield.toggle_flag(2, 0), FlagToggleResult::Removed);
+    assert!(!minefield.is_cleared());
+}
+
+#[test]
+fn wrong_flag_does_not_win() {
+    let mut game = small_game_starting_at(small_config(), (1, 1));
+
+    game.reveal(1, 1);
+    assert_eq!(game.flag(0, 1), FlagToggleResult::Added);
+
+    // Every mine is flagged, and every other spot is revealed, except the wrongly flagged one
+    game.flag(0, 0);
+    game.flag(4, 4);
+    game.reveal(2, 2);
+    assert!(!is_revealed(&game, 0, 1));
+    assert_eq!(game.state(), GameState::Running);
+
+    assert_eq!(game.flag(0, 1), FlagToggleResult::Removed);
+    assert_eq!(game.reveal(0, 1), StepResult::Phew);
+    assert_eq!(game.state(), GameState::Stopped(true));
+}
+
+#[test]
+fn revealed_spot_cannot_be_flagged() {
+    let mut game = small_game(small_config());
+
+    game.reveal(2, 2);
+    assert_eq!(game.flag(3, 3), FlagToggleResult::None);
+    assert_eq!(game.placed_flags(), 0);
+}
+
+#[test]
+fn game_is_lost_on_boom() {
+    let mut game = small_game(small_config());
+
+    game.reveal(2, 2);
+    assert_eq!(game.reveal(0, 0), StepResult::Boom);
+    assert_eq!(game.minefield().spot(0, 0).unwrap().state, SpotState::ExplodedMine);
+    assert_eq!(game.state(), GameState::Stopped(false));
+    assert_eq!(game.take_events().last(), Some(&GameEvent::Finished { is_won: false }));
+
+    // No moves can be made once the game is over
+    assert_eq!(game.reveal(4, 4), StepResult::Invalid);
+    assert_eq!(game.flag(4, 4), FlagToggleResult::None);
+}
+
+#[test]
+fn first_step_is_safe() {
+    let mut game = Game::new(GameDifficulty::HARD);
+
+    assert_eq!(game.reveal(0, 0), StepResult::Phew);
+    assert_eq!(game.minefield().mines(), GameDifficulty::HARD.mines);
+    assert_eq!(game.state(), GameState::Running);
+}
+
+#[test]
+fn seeded_game_starts_on_its_start_spot() {
+    for seed in 0..50 {
+        let config = GameConfig {
+            seed: Seed::Fixed(seed),
+            ..GameDifficulty::HARD
+        };
+        let mut game = Game::new(config);
+        let (x, y) = game.start_spot().unwrap();
+        let elsewhere = ((x + 1) % config.width, y);
+
+        assert_eq!(game.reveal(elsewhere.0, elsewhere.1), StepResult::Invalid);
+        assert_eq!(game.state(), GameState::Ready);
+        assert_eq!(game.clicks(), 0);
+
+        // Flags may still be placed anywhere before the first step, but steps may not
+        assert_eq!(game.flag(elsewhere.0, elsewhere.1), FlagToggleResult::Added);
+        assert_eq!(game.reveal(x, (y + 1) % config.height), StepResult::Invalid);
+
+        assert_eq!(game.reveal(x, y), StepResult::Phew);
+        assert_eq!(game.state(), GameState::Running);
+    }
+}
+
+#[test]
+fn board_without_start_starts_on_a_safe_spot() {
+    let mut game = Game::with_board(small_board(small_config(), None));
+
+    // The top left corner is a mine, so the first opening in reading order is next to its neighbors
+    assert_eq!(game.start_spot(), Some((2, 0)));
+    assert_eq!(game.reveal(0, 0), StepResult::Invalid);
+    assert_eq!(game.reveal(2, 0), StepResult::Phew);
+}
+
+#[test]
+fn no_guess_generator_gives_up_in_time() {
+    // The solver deduces nothing when spots hold several mines, so no minefield can be solved without guessing
+    let config = GameConfig {
+        generator: Generator::NoGuess,
+        mines_per_spot: 2,
+        ..small_config()
+    };
+    let mut game = Game::new(config);
+
+    assert!(!game.may_need_guessing());
+    assert_eq!(game.reveal(2, 2), StepResult::Phew);
+    assert!(game.may_need_guessing());
+    assert_eq!(game.minefield().mines(), config.mines);
+}
+
+#[test]
+fn seeded_no_guess_minefield_is_always_the_same() {
+    // Whether a minefield which needs no guessing is found (the first config) or not (the second), the seed decides
+    for config in [
+        GameConfig {
+            generator: Generator::NoGuess,
+            seed: Seed::Fixed(7),
+            ..GameDifficulty::MEDIUM
+        },
+        GameConfig {
+            generator: Generator::NoGuess,
+            seed: Seed::Fixed(7),
+            mines_per_spot: 2,
+            ..small_config()
+        },
+    ] {
+        let mines: Vec<Vec<(u16, u16)>> = (0..3)
+            .map(|_| {
+                let mut game = Game::new(config);
+                let (x, y) = game.start_spot().unwrap();
+                game.reveal(x, y);
+                let mut mines: Vec<(u16, u16)> = game.minefield().mines_coords().collect();
+                mines.sort();
+                mines
+            })
+            .collect();
+
+        assert_eq!(mines[0], mines[1]);
+        assert_eq!(mines[0], mines[2]);
+    }
+}
+
+#[test]
+fn time_spent_laying_mines_is_not_counted() {
+    // No minefield can be solved without guessing (see above), so laying the mines takes as long as it is allowed to
+    let config = GameConfig {
+        generator: Generator::NoGuess,
+        mines_per_spot: 2,
+        ..small_config()
+    };
+
+    let mut game = Game::new(config);
+    game.reveal(2, 2);
+    assert!(game.millis() < 250, "{} ms", game.millis());
+
+    // Even when the stopwatch was started by a flag
+    let mut game = Game::new(config);
+    game.flag(0, 0);
+    game.reveal(2, 2);
+    assert!(game.millis() < 250, "{} ms", game.millis());
+}
+
+#[test]
+fn no_guess_minefield_needs_no_guessing() {
+    let config = GameConfig {
+        generator: Generator::NoGuess,
+        ..GameDifficulty::MEDIUM
+    };
+    let mut game = Game::new(config);
+
+    game.reveal(8, 8);
+    assert!(!game.may_need_guessing());
+}
+
+#[test]
+fn chord_reveals_neighbors_of_flagged_spot() {
+    let mut game = small_game_starting_at(small_config(), (1, 1));
+
+    assert_eq!(game.reveal(1, 1), StepResult::Phew);
+    assert!(!is_revealed(&game, 2, 2));
+
+    // Not enough flags around the spot
+    assert_eq!(game.chord(1, 1), StepResult::Invalid);
+
+    game.flag(0, 0);
+    assert_eq!(game.chord(1, 1), StepResult::Phew);
+    assert!(is_revealed(&game, 1, 0));
+    assert!(is_revealed(&game, 0, 1));
+
+    // The chord stepped on an opening, which revealed the rest of the empty spots
+    assert!(is_revealed(&game, 2, 2));
+    assert!(is_revealed(&game, 3, 4));
+    assert_eq!(game.state(), GameState::Running);
+}
+
+#[test]
+fn chord_with_wrong_flag_is_lost() {
+    let mut game = small_game_starting_at(small_config(), (1, 1));
+
+    game.reveal(1, 1);
+    game.flag(1, 0);
+
+    assert_eq!(game.chord(1, 1), StepResult::Boom);
+    assert_eq!(game.state(), GameState::Stopped(false));
+}
+
+#[test]
+fn minefield_auto_step_needs_enough_flags() {
+    let mut minefield = Minefield::new(3, 3).with_mines_at(&[(0, 0)]);
+
+    assert_eq!(minefield.step(1, 1), StepResult::Phew);
+    assert_eq!(minefield.auto_step(1, 1), StepResult::Invalid);
+
+    minefield.toggle_flag(0, 0);
+    assert_eq!(minefield.auto_step(1, 1), StepResult::Phew);
+    assert!(minefield.is_cleared());
+}
+
+#[test]
+fn placed_flags_are_counted() {
+    let mut game = small_game_starting_at(small_config(), (4, 0));
+
+    assert_eq!(game.placed_flags(), 0);
+
+    game.flag(0, 0);
+    game.flag(1, 0);
+    assert_eq!(game.placed_flags(), 2);
+
+    game.flag(1, 0);
+    assert_eq!(game.placed_flags(), 1);
+
+    // Flags placed before the first step survive the mines being placed
+    game.reveal(4, 0);
+    assert_eq!(game.placed_flags(), 1);
+    assert_eq!(game.minefield().flags(), 1);
+
+    // Revealed spots can't be flagged
+    assert_eq!(game.flag(4, 0), FlagToggleResult::None);
+    assert_eq!(game.placed_flags(), 1);
+}
+
+#[test]
+fn placed_flags_count_every_flag_on_a_spot() {
+    let config = GameConfig {
+        mines_per_spot: 2,
+        ..small_config()
+    };
+    let mut game = small_game(config);
+
+    game.reveal(2, 2);
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Added);
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Added);
+    assert_eq!(game.placed_flags(), 2);
+
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Removed);
+    assert_eq!(game.placed_flags(), 0);
+}
+
+#[test]
+fn undo_revives_lost_practice_game() {
+    let config = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..small_config()
+    };
+    let mut game = small_game(config);
+
+    game.reveal(2, 2);
+    game.reveal(0, 0);
+    assert_eq!(game.state(), GameState::Stopped(false));
+    assert!(game.can_undo());
+
+    game.take_events();
+    game.undo();
+    assert_eq!(game.state(), GameState::Running);
+    assert_eq!(game.minefield().spot(0, 0).unwrap().state, SpotState::HiddenMine);
+    assert_eq!(game.take_events(), vec![GameEvent::Revived, GameEvent::Undone]);
+}
+
+#[test]
+fn move_which_changes_nothing_is_not_undoable() {
+    let mut game = small_game_starting_at(small_config(), (1, 1));
+
+    game.reveal(1, 1);
+    game.flag(0, 0);
+    game.undo();
+    assert!(game.can_redo());
+
+    // The spot is already revealed
+    assert_eq!(game.reveal(1, 1), StepResult::Invalid);
+    assert_eq!(game.flag(1, 1), FlagToggleResult::None);
+    assert!(game.can_redo());
+    assert!(!game.can_undo());
+
+    // The second chord finds no hidden neighbors left, so one undo takes back the first chord
+    game.redo();
+    game.chord(1, 1);
+    game.chord(1, 1);
+    game.undo();
+    assert!(!is_revealed(&game, 2, 2));
+
+    // And the next one takes back the flag
+    game.undo();
+    assert_eq!(game.placed_flags(), 0);
+    assert!(!game.can_undo());
+}
+
+#[test]
+fn moves_which_change_nothing_keep_a_ranked_game_ranked() {
+    let mut game = small_game_starting_at(small_config(), (1, 1));
+
+    game.reveal(1, 1);
+    game.reveal(1, 1);
+    assert!(!game.can_undo());
+    assert!(!game.is_practice());
+}
+
+#[test]
+fn board_of_running_ranked_game_is_secret() {
+    let mut game = small_game(small_config());
+    game.reveal(2, 2);
+    assert!(game.board().is_none());
+
+    game.flag(0, 0);
+    game.flag(4, 4);
+    assert_eq!(game.state(), GameState::Stopped(true));
+    assert_eq!(game.board().map(|board| board.mines), Some(vec![(0, 0), (4, 4)]));
+
+    let mut game = small_game(GameConfig {
+        play_mode: PlayMode::Practice,
+        ..small_config()
+    });
+    game.reveal(2, 2);
+    assert!(game.board().is_some());
+}
+
+#[test]
+fn replay_ends_on_the_minefield_of_the_game() {
+    let mut game = small_game(small_config());
+
+    game.reveal(2, 2);
+    game.flag(4, 4);
+    // Too few flags around the spot, so the chord changes nothing, and the undo takes back the flag
+    assert_eq!(game.chord(1, 1), StepResult::Invalid);
+    game.undo();
+    game.flag(0, 0);
+    game.reveal(2, 2);
+    game.undo();
+    game.redo();
+
+    let mut viewer = ReplayViewer::new(game.replay().clone());
+    viewer.position = f64::MAX;
+    viewer.advance(0.0);
+    assert!(viewer.is_finished());
+
+    for ((x, y), spot) in game.minefield().spots() {
+        assert_eq!(
+            viewer.minefield.spot(*x, *y).unwrap().state,
+            spot.state,
+            "spot ({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn lost_ranked_game_cannot_be_undone() {
+    let mut game = small_game(small_config());
+
+    game.reveal(2, 2);
+    game.reveal(0, 0);
+    assert!(!game.can_undo());
+}
+
+#[test]
+fn paused_game_takes_no_moves() {
+    let mut game = small_game(small_config());
+
+    game.reveal(2, 2);
+    game.pause();
+    assert!(game.is_paused());
+    assert_eq!(game.flag(0, 0), FlagToggleResult::None);
+
+    game.unpause();
+    assert_eq!(game.flag(0, 0), FlagToggleResult::Added);
+}
+
+#[test]
+fn cursor_goes_around_a_torus_only() {
+    let minefield = Minefield::new(5, 4);
+    assert_eq!(minefield.next_coords(0, 0, Heading::Left), (0, 0));
+    assert_eq!(minefield.next_coords(4, 3, Heading::Down), (4, 3));
+    assert_eq!(minefield.next_coords(2, 2, Heading::Up), (2, 1));
+
+    let torus = Minefield::new(5, 4).with_topology(Topology::Torus);
+    assert_eq!(torus.next_coords(0, 0, Heading::Left), (4, 0));
+    assert_eq!(torus.next_coords(4, 3, Heading::Down), (4, 0));
+}
+
+#[test]
+fn mines_and_wrong_flags_are_shown_once_finished() {
+    let mut game = small_game(small_config());
+    game.flag(1, 1);
+    game.reveal(2, 2);
+    let look = |game: &Game, x, y, is_finished| game.minefield().spot(x, y).unwrap().look(is_finished);
+
+    assert_eq!(look(&game, 0, 0, false), SpotLook::Hidden);
+    assert_eq!(look(&game, 1, 1, false), SpotLook::Flags(1));
+    assert_eq!(look(&game, 2, 2, false), SpotLook::Revealed(0));
+
+    game.flag(4, 4);
+    game.reveal(0, 0);
+    assert_eq!(look(&game, 0, 0, true), SpotLook::Exploded(1));
+    assert_eq!(
+        look(&game, 1, 1, true),
+        SpotLook::CheckedFlags {
+            flags: 1,
+            is_right: false
+        }
+    );
+    assert_eq!(
+        look(&game, 4, 4, true),
+        SpotLook::CheckedFlags {
+            flags: 1,
+            is_right: true
+        }
+    );
+}
+
+#[test]
+fn difficulty_of_presets() {
+    assert_eq!(GameDifficulty::from_config(&GameDifficulty::EASY), GameDifficulty::Easy);
+    assert_eq!(
+        GameDifficulty::from_config(&GameDifficulty::MEDIUM),
+        GameDifficulty::Medium
+    );
+    assert_eq!(GameDifficulty::from_config(&GameDifficulty::HARD), GameDifficulty::Hard);
+    assert_eq!(GameDifficulty::from_config(&small_config()), GameDifficulty::Custom);
+
+    // Only the size of the minefield and the number of mines matter
+    let practice = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..GameDifficulty::HARD
+    };
+    assert_eq!(GameDifficulty::from_config(&practice), GameDifficulty::Hard);
+
+    let more_mines = GameConfig {
+        mines: 11,
+        ..GameDifficulty::EASY
+    };
+    assert_eq!(GameDifficulty::from_config(&more_mines), GameDifficulty::Custom);
+}
+
+#[test]
+fn presets_are_valid() {
+    for config in [GameDifficulty::EASY, GameDifficulty::MEDIUM, GameDifficulty::HARD] {
+        assert!(config.is_valid(), "{:?}", config);
+    }
+}
@@ -0,0 +1,190 @@
+use minesweep_rs::game::{GameConfig, PlayMode, Seed};
+use minesweep_rs::records::{FinishedGame, Records};
+use minesweep_rs::settings_file::SettingsFile;
+use std::collections::HashMap;
+
+mod common;
+use common::{small_config, small_game};
+
+/// Storage which keeps its values in memory, as RON, like eframe does in a file
+#[derive(Default)]
+struct MemoryStorage(HashMap<String, String>);
+
+impl eframe::Storage for MemoryStorage {
+    fn get_string(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_owned(), value);
+    }
+
+    fn flush(&mut self) {}
+}
+
+/// A finished game on a small minefield with mines in two corners, won unless `is_lost`
+fn finished_game(config: GameConfig, is_lost: bool) -> FinishedGame {
+    let mut game = small_game(config);
+    game.reveal(2, 2);
+    if is_lost {
+        game.reveal(0, 0);
+    } else {
+        game.flag(0, 0);
+        game.flag(4, 4);
+    }
+
+    FinishedGame::of(&game, "Tester")
+}
+
+#[test]
+fn won_game_is_recorded() {
+    let mut records = Records::default();
+
+    assert_eq!(records.add(&finished_game(small_config(), false)), Some(0));
+    assert_eq!(records.high_scores.scores(&small_config())[0].name, "Tester");
+    assert_eq!(
+        records.statistics.table(&small_config()).map(|table| table.won),
+        Some(1)
+    );
+    assert_eq!(records.replays[0].player, "Tester");
+}
+
+#[test]
+fn lost_game_has_no_high_score() {
+    let mut records = Records::default();
+
+    assert_eq!(records.add(&finished_game(small_config(), true)), None);
+    assert!(records.high_scores.scores(&small_config()).is_empty());
+    assert_eq!(
+        records
+            .statistics
+            .table(&small_config())
+            .map(|table| (table.played, table.won)),
+        Some((1, 0))
+    );
+    assert_eq!(records.replays.len(), 1);
+}
+
+#[test]
+fn practice_game_only_keeps_its_replay() {
+    let config = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..small_config()
+    };
+    let mut records = Records::default();
+
+    assert_eq!(records.add(&finished_game(config, false)), None);
+    assert!(records.high_scores.scores(&config).is_empty());
+    assert!(records.statistics.table(&config).is_none());
+    assert_eq!(records.replays.len(), 1);
+
+    records.remove_last_replay();
+    assert!(records.replays.is_empty());
+}
+
+#[test]
+fn daily_game_has_its_own_high_scores() {
+    let config = GameConfig {
+        seed: Seed::Daily,
+        ..small_config()
+    };
+    let finished_game = finished_game(config, false);
+    let mut records = Records::default();
+
+    assert_eq!(records.add(&finished_game), Some(0));
+    assert!(records.high_scores.scores(&config).is_empty());
+    assert_eq!(records.daily_high_scores.scores(finished_game.seed, &config).len(), 1);
+}
+
+#[test]
+fn records_are_added_to_those_in_storage() {
+    let mut storage = MemoryStorage::default();
+    let mut records = Records::load(&storage);
+    records.add(&finished_game(small_config(), true));
+
+    // Another app recorded a game while this one was running
+    let mut other_records = Records::load(&storage);
+    other_records.add(&finished_game(small_config(), false));
+    other_records.save(&mut storage);
+
+    // This app adds its own games to the records in storage, rather than writing its own records over them
+    records.merge_stored(&storage);
+    records.save(&mut storage);
+
+    let stored = Records::load(&storage);
+    assert_eq!(stored.high_scores.scores(&small_config()).len(), 1);
+    assert_eq!(
+        stored
+            .statistics
+            .table(&small_config())
+            .map(|table| (table.played, table.won)),
+        Some((2, 1))
+    );
+    assert_eq!(stored.replays.len(), 2);
+
+    // Saved games are not added again
+    records.merge_stored(&storage);
+    assert_eq!(records.replays.len(), 2);
+}
+
+#[test]
+fn revived_game_is_not_added_to_storage() {
+    let config = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..small_config()
+    };
+    let storage = MemoryStorage::default();
+    let mut records = Records::load(&storage);
+
+    records.add(&finished_game(config, true));
+    records.remove_last_replay();
+    records.merge_stored(&storage);
+    assert!(records.replays.is_empty());
+}
+
+#[test]
+fn apps_sharing_a_settings_file_keep_each_others_records() {
+    let dir = std::env::temp_dir().join(format!("minesweep-rs-records-{}", std::process::id()));
+    let path = dir.join("app.ron");
+    let _ = std::fs::remove_file(&path);
+
+    // Both apps start from the same file, which doesn't exist yet
+    let mut terminal_file = SettingsFile::at(path.clone()).expect("A missing file is empty");
+    let mut terminal_records = Records::load(&terminal_file);
+    terminal_records.add(&finished_game(small_config(), false));
+
+    let mut desktop_file = SettingsFile::at(path.clone()).unwrap();
+    let mut desktop_records = Records::load(&desktop_file);
+    desktop_records.add(&finished_game(small_config(), true));
+    desktop_records.merge_stored(&SettingsFile::at(path.clone()).unwrap());
+    desktop_records.save(&mut desktop_file);
+    eframe::Storage::flush(&mut desktop_file);
+
+    // The terminal app reads the file again when it exits
+    assert!(terminal_file.reload());
+    terminal_records.merge_stored(&terminal_file);
+    terminal_records.save(&mut terminal_file);
+    eframe::Storage::flush(&mut terminal_file);
+
+    // And the desktop app does the same the next time it saves
+    desktop_records.merge_stored(&SettingsFile::at(path.clone()).unwrap());
+    let stored = Records::load(&SettingsFile::at(path.clone()).unwrap());
+    for records in [&stored, &desktop_records] {
+        assert_eq!(
+            records
+                .statistics
+                .table(&small_config())
+                .map(|table| (table.played, table.won)),
+            Some((2, 1))
+        );
+        assert_eq!(records.replays.len(), 2);
+    }
+
+    // A file which can't be read is not taken for an empty one
+    std::fs::write(&path, "(\"half written").unwrap();
+    assert!(SettingsFile::at(path.clone()).is_none());
+    assert!(!terminal_file.reload());
+    assert_eq!(Records::load(&terminal_file).replays.len(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,153 @@
+//! Everything the apps keep in storage must read back what they wrote. eframe stores each value as RON.
+
+use minesweep_rs::app::KeyBindings;
+use minesweep_rs::board::Board;
+use minesweep_rs::game::{
+    FirstClick, Game, GameConfig, GameDifficulty, GameState, Generator, PlayMode, SavedGame, Seed,
+};
+use minesweep_rs::highscores::{DailyHighScores, HighScore, HighScores};
+use minesweep_rs::minefield::{SpotState, Topology};
+use minesweep_rs::statistics::{GameRecord, Statistics};
+use serde::{de::DeserializeOwned, Serialize};
+
+mod common;
+use common::{small_config, small_game};
+
+/// Write a value the way eframe does, and read it back
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    let text = ron::ser::to_string(value).unwrap();
+    ron::from_str(&text).unwrap_or_else(|e| panic!("Could not read back {}: {}", text, e))
+}
+
+#[test]
+fn game_config_round_trip() {
+    let config = GameConfig {
+        width: 12,
+        height: 9,
+        mines: 20,
+        first_click: FirstClick::Opening,
+        generator: Generator::NoGuess,
+        play_mode: PlayMode::Practice,
+        topology: Topology::Hex,
+        mines_per_spot: 2,
+        seed: Seed::Fixed(42),
+    };
+
+    assert_eq!(round_trip(&config), config);
+}
+
+#[test]
+fn key_bindings_round_trip() {
+    let key_bindings = KeyBindings::default();
+
+    assert_eq!(round_trip(&key_bindings), key_bindings);
+}
+
+#[test]
+fn high_scores_round_trip() {
+    let mut high_scores = HighScores::default();
+    high_scores.add(&GameDifficulty::EASY, HighScore::new("Alice", 12_345, 0));
+    high_scores.add(&GameDifficulty::EASY, HighScore::new("Bob", 9_876, 2));
+    high_scores.add(&GameDifficulty::HARD, HighScore::new("Alice", 123_456, 0));
+
+    let stored = round_trip(&high_scores);
+
+    assert_eq!(
+        stored.scores(&GameDifficulty::EASY),
+        high_scores.scores(&GameDifficulty::EASY)
+    );
+    assert_eq!(
+        stored.scores(&GameDifficulty::HARD),
+        high_scores.scores(&GameDifficulty::HARD)
+    );
+    assert_eq!(stored.scores(&GameDifficulty::EASY)[0].millis, 9_876);
+}
+
+#[test]
+fn daily_high_scores_round_trip() {
+    let mut daily_high_scores = DailyHighScores::default();
+    daily_high_scores.add(20260101, &GameDifficulty::MEDIUM, HighScore::new("Alice", 54_321, 1));
+
+    let stored = round_trip(&daily_high_scores);
+
+    assert_eq!(
+        stored.scores(20260101, &GameDifficulty::MEDIUM),
+        daily_high_scores.scores(20260101, &GameDifficulty::MEDIUM)
+    );
+}
+
+#[test]
+fn statistics_round_trip() {
+    let mut statistics = Statistics::default();
+    statistics.add(&GameDifficulty::EASY, GameRecord::new(true, 12_345, 30, 20));
+    statistics.add(&GameDifficulty::EASY, GameRecord::new(false, 3_000, 5, 20));
+
+    let stored = round_trip(&statistics);
+    let stored_table = stored.table(&GameDifficulty::EASY).unwrap();
+
+    assert_eq!((stored_table.played, stored_table.won), (2, 1));
+    assert_eq!(
+        stored_table.games,
+        statistics.table(&GameDifficulty::EASY).unwrap().games
+    );
+}
+
+#[test]
+fn saved_game_round_trip() {
+    let config = GameConfig {
+        mines: 3,
+        mines_per_spot: 2,
+        ..small_config()
+    };
+    let mut game = Game::with_board(Board {
+        config,
+        mines: vec![(0, 0), (0, 0), (4, 4)],
+        start: Some((2, 2)),
+    });
+    game.reveal(2, 2);
+    game.flag(0, 0);
+    game.flag(0, 0);
+
+    let saved: SavedGame = round_trip(&game.saved());
+    let resumed = Game::resume(config, saved).expect("The saved game fits its config");
+
+    assert_eq!(resumed.state(), GameState::Running);
+    assert_eq!(resumed.placed_flags(), 2);
+    assert_eq!(resumed.clicks(), game.clicks());
+    assert_eq!(resumed.replay().actions, game.replay().actions);
+    assert_eq!(
+        round_trip(&vec![game.replay().clone()])[0].actions,
+        game.replay().actions
+    );
+
+    let corner = resumed.minefield().spot(0, 0).unwrap();
+    assert_eq!(
+        (corner.state, corner.mines, corner.flags),
+        (SpotState::FlaggedMine, 2, 2)
+    );
+    for (coords, spot) in game.minefield().spots() {
+        assert_eq!(
+            resumed.minefield().spot(coords.0, coords.1).unwrap().state,
+            spot.state,
+            "{:?}",
+            coords
+        );
+    }
+}
+
+#[test]
+fn saved_board_game_is_resumed_with_the_board_config() {
+    let config = GameConfig {
+        play_mode: PlayMode::Practice,
+        ..small_config()
+    };
+    let mut game = small_game(config);
+    game.reveal(2, 2);
+
+    // The player's own config is another one
+    let saved: SavedGame = round_trip(&game.saved());
+    let resumed = Game::resume(GameDifficulty::HARD, saved).expect("The saved game fits its board");
+
+    assert_eq!(*resumed.config(), config);
+    assert!(resumed.is_practice());
+}